@@ -9,6 +9,7 @@ fn main() {
     let mut mr = Mr {
         landed: Rc::new(RefCell::new(false)),
         retry: Rc::new(RefCell::new(false)),
+        landing_time: Rc::new(RefCell::new(None)),
     };
     mr.run();
 }
@@ -1,6 +1,20 @@
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::capabilities::Capabilities;
 use crate::engine_v2::collision::Collision;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::entity::credits::credit_lines;
+use crate::engine_v2::entity::object::Object;
+use crate::engine_v2::options::EngineOptions;
+use crate::engine_v2::random;
+use crate::engine_v2::replay;
+use crate::engine_v2::replay::ReplayHeader;
+use crate::storage::Storage;
+use crate::tools::get_terminal_size;
 use std::any::type_name;
+use std::io;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::io::stderr;
+use std::io::stdout;
 
 #[cfg(unix)]
 use crate::signal;
@@ -15,15 +29,111 @@ pub trait CommandV2 {
         #[cfg(unix)]
         signal::ignore_sigint();
 
+        // Options applied by the engine to every command
+        match EngineOptions::from_env()
+            .and_then(|defaults| EngineOptions::parse(defaults, std::env::args()))
+            .and_then(|(options, _)| start_session(options, &self.name()))
+        {
+            Ok(options) => EngineOptions::init_global(options),
+            Err(error) => {
+                let _ = writeln!(stderr(), "{}: {}", self.name(), error);
+                std::process::exit(2);
+            }
+        }
+
+        if EngineOptions::global().credits() {
+            self.print_credits();
+            return;
+        }
+
         self.execute();
+
+        if EngineOptions::global().print_seed() {
+            let _ = writeln!(stderr(), "{}: seed {}", self.name(), random::seed());
+        }
+
+        // Terminated by SIGTERM or SIGHUP, exit like the default action would
+        #[cfg(unix)]
+        if let Some(sig) = signal::termination_signal() {
+            std::process::exit(128 + sig);
+        }
     }
 
     fn execute(&mut self);
 
-    fn get_all_objects(&self) -> Vec<fn() -> ObjectRef>;
+    /// Lists the artists of the objects of the scene selected by the arguments.
+    fn print_credits(&mut self) {
+        let (objects, _) = self.select_objects(command_args());
+        let hyperlinks = Capabilities::global().hyperlinks() && stdout().is_terminal();
+        let lines = credit_lines(&objects, hyperlinks);
+        let mut stdout = stdout();
+        if lines.is_empty() {
+            let _ = writeln!(stdout, "{}: no credited art in this scene", self.name());
+        }
+        for line in lines {
+            let _ = writeln!(stdout, "{}", line);
+        }
+    }
+
+    /// Opens the persistent store of the command, kept between runs.
+    fn storage(&self) -> io::Result<Storage> {
+        Storage::open(&self.name())
+    }
+
+    fn get_all_objects(&self) -> Vec<fn() -> Object>;
 
     fn select_objects(
         &mut self,
         args: impl Iterator<Item = String>,
-    ) -> (Vec<ObjectRef>, Vec<Collision>);
+    ) -> (ObjectArena, Vec<Collision>);
+}
+
+/// Seeds the random generator, then starts recording or replaying the input events
+/// when asked by the options.
+fn start_session(mut options: EngineOptions, command: &str) -> Result<EngineOptions, String> {
+    if let Some(path) = options.replay().cloned() {
+        let path = path.as_path();
+        let header = replay::start_replay(path)
+            .map_err(|error| format!("cannot replay {}: {}", path.display(), error))?;
+        if header.command != command {
+            return Err(format!(
+                "{} is a replay of {}, not {}",
+                path.display(),
+                header.command,
+                command
+            ));
+        }
+        // Same seed and speed so the run plays back tick for tick
+        random::init_seed(header.seed);
+        options.set_speed(header.speed);
+        return Ok(options);
+    }
+    if let Some(seed) = options.seed() {
+        random::init_seed(seed);
+    }
+    if let Some(path) = options.record().cloned() {
+        let path = path.as_path();
+        let header = ReplayHeader {
+            command: command.to_string(),
+            seed: random::seed(),
+            terminal_size: get_terminal_size(),
+            speed: options.speed(),
+            args: command_args().collect(),
+        };
+        replay::start_recording(path, &header)
+            .map_err(|error| format!("cannot record to {}: {}", path.display(), error))?;
+    }
+    Ok(options)
+}
+
+/// Returns the command line arguments without the engine options,
+/// or the recorded ones when replaying.
+pub fn command_args() -> impl Iterator<Item = String> {
+    if let Some(header) = replay::replayed_header() {
+        return header.args.into_iter();
+    }
+    EngineOptions::parse(EngineOptions::default(), std::env::args())
+        .map(|(_, args)| args)
+        .unwrap_or_else(|_| std::env::args().collect())
+        .into_iter()
 }
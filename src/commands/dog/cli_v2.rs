@@ -1,27 +1,31 @@
 use crate::command::CommandV2;
+use crate::command::command_args;
+use crate::commands::dog::objects::get_bitmap_ball;
 use crate::commands::dog::objects::get_object_dog;
 use crate::commands::dog::objects::get_object_domain;
+use crate::engine_v2::arena::ObjectArena;
 use crate::engine_v2::collision::Collision;
 use crate::engine_v2::collision::ScreenEdge;
 use crate::engine_v2::engine::Engine;
 use crate::engine_v2::entity::movement::Movement;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::entity::object::Object;
 use crate::engine_v2::position::Position;
 use crate::engine_v2::position::XTermPosition;
 use crate::engine_v2::position::YTermPosition;
 use crate::tools::filter_params_regex;
+use crate::tools::parse_args;
 
 pub struct Dog {}
 
 impl CommandV2 for Dog {
-    fn get_all_objects(&self) -> Vec<fn() -> ObjectRef> {
+    fn get_all_objects(&self) -> Vec<fn() -> Object> {
         vec![get_object_dog]
     }
     fn select_objects(
         &mut self,
         args: impl Iterator<Item = String>,
-    ) -> (Vec<ObjectRef>, Vec<Collision>) {
-        let mut objects: Vec<ObjectRef> = Vec::new();
+    ) -> (ObjectArena, Vec<Collision>) {
+        let mut objects = ObjectArena::new();
         let mut collisions: Vec<Collision> = Vec::new();
         // Regex to match domains
         let params = [r"^(?:[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?\.)+[a-zA-Z]{2,}$"];
@@ -32,41 +36,57 @@ impl CommandV2 for Dog {
         if !dparams.is_empty() {
             domain_object_txt = dparams[0].to_string();
         }
-        let domain_object = get_object_domain(domain_object_txt);
+        let mut domain_object = get_object_domain(domain_object_txt);
+        if dparams.is_empty() {
+            domain_object.set_bitmap(Some(get_bitmap_ball()));
+        }
         let movement = Movement::new_circular(
             Position::new(XTermPosition::LeftOut, YTermPosition::Coord(9), 0),
             Position::new(XTermPosition::RightOut, YTermPosition::Coord(9), 0),
             8,
             15,
         );
-        domain_object.borrow_mut().set_movement(movement);
-        objects.push(domain_object);
+        domain_object.set_movement(movement);
+        // Catch the ball with a click, click again to throw it back
+        domain_object.on_click(|ball, engine| {
+            let o = &mut engine.objects_mut()[ball];
+            if o.is_movement_active() {
+                o.deactivate_movement();
+            } else {
+                o.activate_movement();
+            }
+        });
+        objects.insert(domain_object);
 
         // Dog object
-        let dog_object = get_object_dog();
+        let mut dog_object = get_object_dog();
         let movement = Movement::new_linear(
             Position::new(XTermPosition::LeftOut, YTermPosition::Coord(3), 0),
             Position::new(XTermPosition::RightOut, YTermPosition::Coord(3), 0),
             12,
         );
+        dog_object.set_movement(movement);
+        let dog = objects.insert(dog_object);
 
         let collision = Collision::new_edge(
-            dog_object.clone(),
+            dog,
             ScreenEdge::RightWithObjectLeftSide,
             move |_, _, engine| {
                 engine.stop();
             },
         );
 
-        dog_object.borrow_mut().set_movement(movement);
-        objects.push(dog_object);
         collisions.push(collision);
 
         (objects, collisions)
     }
     fn execute(&mut self) {
-        let (objects, collisions) = self.select_objects(std::env::args());
+        let args: Vec<String> = command_args().collect();
+        let (flags, _) = parse_args(args.clone(), &['m'], &["mouse"], &[]);
+        let (objects, collisions) = self.select_objects(args.into_iter());
         let mut engine = Engine::new(objects, collisions, 0);
+        // The mouse is left to the terminal unless asked, so text can still be selected
+        engine.set_mouse_capture(flags.contains("m") || flags.contains("mouse"));
         engine.run();
     }
 }
@@ -82,18 +102,20 @@ mod tests {
         let mut dog = Dog {};
         let args: Vec<String> = vec![String::from("dog")];
         let (objects, collisions) = dog.select_objects(args.into_iter());
+        let handles = objects.handles();
 
-        assert_eq!(objects[1].borrow_mut().movement().speed(), 12);
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 8);
+        assert_eq!(objects[handles[1]].movement().speed(), 12);
+        assert_eq!(objects[handles[0]].movement().speed(), 8);
         assert_eq!(
-            objects[1].borrow_mut().movement().direction(),
+            objects[handles[1]].movement().direction(),
             Direction::Linear
         );
         assert_eq!(
-            objects[0].borrow_mut().movement().direction(),
+            objects[handles[0]].movement().direction(),
             Direction::Circular
         );
-        assert_eq!(objects[0].borrow_mut().current_frame().content(), "()");
+        assert_eq!(objects[handles[0]].current_frame().content(), "()");
+        assert!(objects[handles[0]].bitmap().is_some());
         assert_eq!(collisions.len(), 1);
     }
 
@@ -102,21 +124,36 @@ mod tests {
         let mut dog = Dog {};
         let args: Vec<String> = vec![String::from("dog"), String::from("debian.org")];
         let (objects, collisions) = dog.select_objects(args.into_iter());
+        let handles = objects.handles();
 
-        assert_eq!(objects[1].borrow_mut().movement().speed(), 12);
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 8);
+        assert_eq!(objects[handles[1]].movement().speed(), 12);
+        assert_eq!(objects[handles[0]].movement().speed(), 8);
         assert_eq!(
-            objects[1].borrow_mut().movement().direction(),
+            objects[handles[1]].movement().direction(),
             Direction::Linear
         );
         assert_eq!(
-            objects[0].borrow_mut().movement().direction(),
+            objects[handles[0]].movement().direction(),
             Direction::Circular
         );
-        assert_eq!(
-            objects[0].borrow_mut().current_frame().content(),
-            "debian.org"
-        );
+        assert_eq!(objects[handles[0]].current_frame().content(), "debian.org");
+        assert!(objects[handles[0]].bitmap().is_none());
         assert_eq!(collisions.len(), 1);
     }
+
+    #[test]
+    fn test_click_ball() {
+        let mut dog = Dog {};
+        let args: Vec<String> = vec![String::from("dog")];
+        let (objects, collisions) = dog.select_objects(args.into_iter());
+        let ball = objects.handles()[0];
+        let mut engine = Engine::new(objects, collisions, 0);
+
+        let click = engine.objects()[ball].click_action().unwrap();
+        assert!(engine.objects()[ball].is_movement_active());
+        click(ball, &mut engine);
+        assert!(!engine.objects()[ball].is_movement_active());
+        click(ball, &mut engine);
+        assert!(engine.objects()[ball].is_movement_active());
+    }
 }
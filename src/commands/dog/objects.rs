@@ -1,8 +1,10 @@
+use crate::engine_v2::entity::credits::Credits;
 use crate::engine_v2::entity::frame::Frame;
 use crate::engine_v2::entity::object::Object;
-use crate::engine_v2::entity::object::ObjectRef;
 use crate::engine_v2::entity::sprite::Sprite;
 use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
+use crate::engine_v2::entity::trail::Trail;
+use crate::engine_v2::graphics::Bitmap;
 
 const FRAME_DOG1: &str = include_str!("frames/dog1.adoc");
 const FRAME_DOG2: &str = include_str!("frames/dog2.adoc");
@@ -11,7 +13,20 @@ const FRAME_DOG4: &str = include_str!("frames/dog4.adoc");
 const FRAME_DOG5: &str = include_str!("frames/dog5.adoc");
 const FRAME_DOG6: &str = include_str!("frames/dog6.adoc");
 
-pub fn get_object_dog() -> ObjectRef {
+const BITMAP_BALL: &str = "
+..rrrr..
+.rwrrrrr
+rwrrrrrr
+rrrrrrrr
+rrrrrrrd
+rrrrrrdd
+.rrrrdd.
+..rddd..
+";
+
+const DOG_SOURCE: &str = "https://www.asciiart.eu/animals/dogs";
+
+pub fn get_object_dog() -> Object {
     let frames = vec![
         Frame::new(FRAME_DOG1),
         Frame::new(FRAME_DOG2),
@@ -22,11 +37,28 @@ pub fn get_object_dog() -> ObjectRef {
     ];
     let anim = SpriteAnimation::new_movement_based(frames.clone(), true);
     let sprite = Sprite::new(anim, true);
-    Object::new(9, String::from("Running dog"), vec![sprite], None)
+    let mut object = Object::new(9, String::from("Running dog"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(DOG_SOURCE)));
+    object
 }
 
-pub fn get_object_domain(domain_name: String) -> ObjectRef {
+pub fn get_object_domain(domain_name: String) -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(domain_name));
     let sprite = Sprite::new(anim, true);
-    Object::new(9, String::from("Domain"), vec![sprite], None)
+    let mut object = Object::new(9, String::from("Domain"), vec![sprite], None);
+    // Comet tail behind the ball
+    object.set_trail(Some(Trail::new(4)));
+    object
+}
+
+/// A red ball drawn over the "()" on terminals showing bitmaps.
+pub fn get_bitmap_ball() -> Bitmap {
+    Bitmap::from_pixel_art(
+        BITMAP_BALL,
+        &[
+            ('r', [220, 30, 30, 255]),
+            ('d', [140, 10, 10, 255]),
+            ('w', [255, 255, 255, 255]),
+        ],
+    )
 }
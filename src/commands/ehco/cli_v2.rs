@@ -1,32 +1,35 @@
 use crate::engine_v2::collision::LineOrientation;
 use crate::engine_v2::collision::ScreenEdge;
-use rand::rng;
 use rand::seq::SliceRandom;
 
 use crate::command::CommandV2;
+use crate::command::command_args;
 use crate::commands::ehco::objects::get_object_parrot;
 use crate::commands::ehco::objects::get_word_object;
+use crate::engine_v2::arena::ObjectArena;
 use crate::engine_v2::collision::Collision;
 use crate::engine_v2::engine::Engine;
 use crate::engine_v2::entity::movement::Movement;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::entity::object::Object;
 use crate::engine_v2::position::Position;
 use crate::engine_v2::position::XTermPosition;
 use crate::engine_v2::position::YTermPosition;
+use crate::engine_v2::random;
 use rand::RngExt;
 
 pub struct Ehco {}
 
 impl CommandV2 for Ehco {
-    fn get_all_objects(&self) -> Vec<fn() -> ObjectRef> {
+    fn get_all_objects(&self) -> Vec<fn() -> Object> {
         Vec::new()
     }
 
     fn select_objects(
         &mut self,
         args: impl Iterator<Item = String>,
-    ) -> (Vec<ObjectRef>, Vec<Collision>) {
-        let mut objects: Vec<ObjectRef> = Vec::new();
+    ) -> (ObjectArena, Vec<Collision>) {
+        let mut objects = ObjectArena::new();
+        let mut word_objects = Vec::new();
         let mut collisions: Vec<Collision> = Vec::new();
         let speed = 10;
         let start_x = 0;
@@ -51,8 +54,7 @@ impl CommandV2 for Ehco {
                 .split_whitespace()
                 .map(|w| {
                     let mut chars: Vec<char> = w.chars().collect();
-                    let mut rng = rng();
-                    chars.shuffle(&mut rng);
+                    chars.shuffle(&mut random::rng());
                     chars.into_iter().collect::<String>()
                 })
                 .collect();
@@ -60,14 +62,12 @@ impl CommandV2 for Ehco {
             words.extend(scrambled);
         }
 
-        let parrot_object = get_object_parrot();
-        let parrot_width = parrot_object.borrow().current_frame().get_width();
+        let mut parrot_object = get_object_parrot();
+        let parrot_width = parrot_object.current_frame().get_width();
 
         for (index, word) in words.iter().enumerate() {
-            let mut rng = rand::rng();
-
-            let n: i32 = rng.random_range(0 - start_y..=max_y);
-            let object = get_word_object(word.to_string());
+            let n: i32 = random::rng().random_range(0 - start_y..=max_y);
+            let mut object = get_word_object(word.to_string());
             let y = start_y + n;
             let movement = Movement::new_linear(
                 Position::new(
@@ -80,13 +80,13 @@ impl CommandV2 for Ehco {
                 Position::new(XTermPosition::RightOut, YTermPosition::Coord(y), 10),
                 speed,
             );
-            object.borrow_mut().set_movement(movement);
+            object.set_movement(movement);
             if index > 0 {
-                object.borrow_mut().deactivate_movement();
-                object.borrow_mut().set_visible(false);
+                object.deactivate_movement();
+                object.set_visible(false);
             }
 
-            objects.push(object);
+            word_objects.push(objects.insert(object));
         }
 
         let movement = Movement::new_stationary(
@@ -97,31 +97,28 @@ impl CommandV2 for Ehco {
             ),
             0,
         );
-        parrot_object.borrow_mut().set_movement(movement);
-        let parrot_id = parrot_object.borrow().id();
+        parrot_object.set_movement(movement);
+        let parrot = objects.insert(parrot_object);
 
-        for (index, word_object) in objects.iter().enumerate() {
+        for (index, word_object) in word_objects.iter().enumerate() {
             let word = words[index].clone();
-            if index + 1 >= objects.len() {
+            if index + 1 >= word_objects.len() {
                 // Last object of the list
                 let collision = Collision::new_line(
-                    word_object.clone(),
+                    *word_object,
                     start_x + parrot_width as i32 + word.len() as i32 + start_x_delta,
                     LineOrientation::Vertical,
                     move |_, _, _, counter, engine| {
                         if counter == word.len() * speed as usize {
-                            for object in engine.objects_mut() {
-                                if object.borrow().id() == parrot_id {
-                                    object.borrow_mut().reset_animation(0);
-                                    object.borrow_mut().deactivate_sprite();
-                                }
-                            }
+                            let parrot_object = &mut engine.objects_mut()[parrot];
+                            parrot_object.reset_animation(0);
+                            parrot_object.deactivate_sprite();
                         }
                     },
                 );
                 collisions.push(collision);
                 let collision = Collision::new_edge(
-                    word_object.clone(),
+                    *word_object,
                     ScreenEdge::RightWithObjectLeftSide,
                     move |_, counter, engine| {
                         if counter == 100 {
@@ -132,20 +129,17 @@ impl CommandV2 for Ehco {
                 collisions.push(collision);
             } else {
                 // Any object of the list besises  the last one
-                let next_object_shape_id = objects[index + 1].borrow().id();
+                let next_word_object = word_objects[index + 1];
 
                 let collision = Collision::new_line(
-                    word_object.clone(),
+                    *word_object,
                     start_x + parrot_width as i32 + word.len() as i32 - start_x_delta + 1,
                     crate::engine_v2::collision::LineOrientation::Vertical,
                     move |_, _, _, counter, engine| {
                         if counter == 1 {
-                            for object in engine.objects_mut() {
-                                if object.borrow().id() == next_object_shape_id {
-                                    object.borrow_mut().set_visible(true);
-                                    object.borrow_mut().activate_movement();
-                                }
-                            }
+                            let next_object = &mut engine.objects_mut()[next_word_object];
+                            next_object.set_visible(true);
+                            next_object.activate_movement();
                         }
                     },
                 );
@@ -153,13 +147,12 @@ impl CommandV2 for Ehco {
             }
         }
 
-        objects.push(parrot_object);
         (objects, collisions)
     }
 
     fn execute(&mut self) {
         // Collect all args except the binary name
-        let (objects, collisions) = self.select_objects(std::env::args());
+        let (objects, collisions) = self.select_objects(command_args());
         let mut engine = Engine::new(objects, collisions, 0);
         engine.run();
     }
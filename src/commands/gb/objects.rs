@@ -1,6 +1,5 @@
 use crate::engine_v2::entity::frame::Frame;
 use crate::engine_v2::entity::object::Object;
-use crate::engine_v2::entity::object::ObjectRef;
 use crate::engine_v2::entity::sprite::Sprite;
 use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
 
@@ -16,13 +15,13 @@ const FRAME_SHAPE7_2: &str = include_str!("frames/shape_7_2.adoc");
 const FRAME_SHAPE8_1: &str = include_str!("frames/shape_8_1.adoc");
 const FRAME_SHAPE8_2: &str = include_str!("frames/shape_8_2.adoc");
 
-pub fn get_object_gameboy() -> ObjectRef {
+pub fn get_object_gameboy() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_GAMEBOY));
     let sprite = Sprite::new(anim, false);
     Object::new(50, String::from("Gameboy"), vec![sprite], None)
 }
 
-pub fn get_object_shape(id: usize, speed: usize) -> ObjectRef {
+pub fn get_object_shape(id: usize, speed: usize) -> Object {
     let frames = match id {
         1 => vec![Frame::new(FRAME_SHAPE1_1), Frame::new(FRAME_SHAPE1_2)],
         2 => vec![Frame::new(FRAME_SHAPE2_1), Frame::new(FRAME_SHAPE2_2)],
@@ -4,23 +4,25 @@ use crate::commands::gti::objects::get_object_pull;
 use crate::commands::gti::objects::get_object_push;
 use crate::commands::gti::objects::get_object_std;
 use crate::commands::gti::objects::get_object_tag;
+use crate::engine_v2::arena::ObjectArena;
 use crate::engine_v2::collision::Collision;
 use crate::engine_v2::collision::ScreenEdge;
 use crate::engine_v2::engine::Engine;
 use crate::engine_v2::entity::movement::Direction;
 use crate::engine_v2::entity::movement::Movement;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::entity::object::Object;
 use crate::engine_v2::position::Position;
 use crate::engine_v2::position::XTermPosition;
 use crate::engine_v2::position::YTermPosition;
 use crate::tools::parse_args;
 
 use crate::command::CommandV2;
+use crate::command::command_args;
 
 pub struct Gti {}
 
 impl CommandV2 for Gti {
-    fn get_all_objects(&self) -> Vec<fn() -> ObjectRef> {
+    fn get_all_objects(&self) -> Vec<fn() -> Object> {
         vec![
             get_object_std,
             get_object_commit,
@@ -33,7 +35,7 @@ impl CommandV2 for Gti {
     fn select_objects(
         &mut self,
         args: impl Iterator<Item = String>,
-    ) -> (Vec<ObjectRef>, Vec<Collision>) {
+    ) -> (ObjectArena, Vec<Collision>) {
         let short_flags: &[char] = &[];
         let long_flags: &[&str] = &[];
         let params = ["tag", "push", "pull", "commit"];
@@ -57,38 +59,38 @@ impl CommandV2 for Gti {
         }
 
         let mut collisions: Vec<Collision> = Vec::new();
-        let mut objects: Vec<ObjectRef> = Vec::new();
+        let mut objects = ObjectArena::new();
         if is_loop {
             let trajectory = Movement::new_stationary(
                 Position::new(XTermPosition::Coord(2), YTermPosition::Coord(10), 0),
                 200,
             );
-            object.borrow_mut().set_movement(trajectory);
-            objects.push(object);
+            object.set_movement(trajectory);
+            objects.insert(object);
         } else {
             let movement = Movement::new_linear(
                 Position::new(XTermPosition::LeftOut, YTermPosition::Coord(10), 0),
                 Position::new(XTermPosition::RightOut, YTermPosition::Coord(10), 0),
                 speed,
             );
-            object.borrow_mut().set_movement(movement);
+            object.set_movement(movement);
+            let car = objects.insert(object);
             let collision = Collision::new_edge(
-                object.clone(),
+                car,
                 ScreenEdge::RightWithObjectLeftSide,
                 move |_, _, engine| {
                     engine.stop();
                 },
             );
             collisions.push(collision);
-            objects.push(object);
         }
         (objects, collisions)
     }
     fn execute(&mut self) {
-        let (objects, collisions) = self.select_objects(std::env::args());
+        let (objects, collisions) = self.select_objects(command_args());
         let mut ttl = 0;
         // Get direction of the first object
-        let car_direction = objects[0].borrow_mut().movement().direction();
+        let car_direction = objects[objects.handles()[0]].movement().direction();
         if car_direction == Direction::Stationary {
             ttl = 300;
         }
@@ -108,9 +110,10 @@ mod tests {
         let mut gti = Gti {};
         let args: Vec<String> = vec![String::from("gti")];
         let (objects, collisions) = gti.select_objects(args.into_iter());
+        let car = objects.handles()[0];
 
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 2);
-        assert_eq!(objects[0].borrow().tdid(), 9);
+        assert_eq!(objects[car].movement().speed(), 2);
+        assert_eq!(objects[car].tdid(), 9);
         assert_eq!(collisions.len(), 1);
     }
     #[test]
@@ -118,9 +121,10 @@ mod tests {
         let mut gti = Gti {};
         let args: Vec<String> = vec![String::from("gti"), String::from("push")];
         let (objects, collisions) = gti.select_objects(args.into_iter());
+        let car = objects.handles()[0];
 
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 10);
-        assert_eq!(objects[0].borrow_mut().tdid(), 11);
+        assert_eq!(objects[car].movement().speed(), 10);
+        assert_eq!(objects[car].tdid(), 11);
         assert_eq!(collisions.len(), 1);
     }
     #[test]
@@ -128,9 +132,10 @@ mod tests {
         let mut gti = Gti {};
         let args: Vec<String> = vec![String::from("gti"), String::from("pull")];
         let (objects, collisions) = gti.select_objects(args.into_iter());
+        let car = objects.handles()[0];
 
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 5);
-        assert_eq!(objects[0].borrow_mut().tdid(), 10);
+        assert_eq!(objects[car].movement().speed(), 5);
+        assert_eq!(objects[car].tdid(), 10);
         assert_eq!(collisions.len(), 1);
     }
 
@@ -139,9 +144,10 @@ mod tests {
         let mut gti = Gti {};
         let args: Vec<String> = vec![String::from("gti"), String::from("commit")];
         let (objects, collisions) = gti.select_objects(args.into_iter());
+        let car = objects.handles()[0];
 
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 0);
-        assert_eq!(objects[0].borrow_mut().tdid(), 13);
+        assert_eq!(objects[car].movement().speed(), 0);
+        assert_eq!(objects[car].tdid(), 13);
         assert_eq!(collisions.len(), 0);
     }
     #[test]
@@ -149,9 +155,10 @@ mod tests {
         let mut gti = Gti {};
         let args: Vec<String> = vec![String::from("gti"), String::from("tag")];
         let (objects, collisions) = gti.select_objects(args.into_iter());
+        let car = objects.handles()[0];
 
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 0);
-        assert_eq!(objects[0].borrow_mut().tdid(), 12);
+        assert_eq!(objects[car].movement().speed(), 0);
+        assert_eq!(objects[car].tdid(), 12);
         assert_eq!(collisions.len(), 0);
     }
 }
@@ -1,6 +1,6 @@
+use crate::engine_v2::entity::credits::Credits;
 use crate::engine_v2::entity::frame::Frame;
 use crate::engine_v2::entity::object::Object;
-use crate::engine_v2::entity::object::ObjectRef;
 use crate::engine_v2::entity::sprite::Sprite;
 use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
 
@@ -17,30 +17,39 @@ const FRAME_COMMIT1: &str = include_str!("frames/commit1.adoc");
 const FRAME_COMMIT2: &str = include_str!("frames/commit2.adoc");
 const FRAME_COMMIT3: &str = include_str!("frames/commit3.adoc");
 
-pub fn get_object_std() -> ObjectRef {
+const GTI_SOURCE: &str = "https://github.com/rwos/gti";
+const GTI_AUTHOR: &str = "Richard Wossal";
+
+pub fn get_object_std() -> Object {
     let frames = vec![Frame::new(FRAME_STD1), Frame::new(FRAME_STD2)];
     let anim = SpriteAnimation::new_movement_based(frames, true);
     let sprite = Sprite::new(anim, true);
 
-    Object::new(9, String::from("Golf GTI"), vec![sprite], None)
+    let mut object = Object::new(9, String::from("Golf GTI"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(GTI_SOURCE).with_author(GTI_AUTHOR)));
+    object
 }
 
-pub fn get_object_pull() -> ObjectRef {
+pub fn get_object_pull() -> Object {
     let frames = vec![Frame::new(FRAME_PULL1), Frame::new(FRAME_PULL2)];
     let anim = SpriteAnimation::new_movement_based(frames, true);
     let sprite = Sprite::new(anim, true);
-    Object::new(10, String::from("Golf GTI pulled"), vec![sprite], None)
+    let mut object = Object::new(10, String::from("Golf GTI pulled"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(GTI_SOURCE).with_author(GTI_AUTHOR)));
+    object
 }
 
-pub fn get_object_push() -> ObjectRef {
+pub fn get_object_push() -> Object {
     let frames = vec![Frame::new(FRAME_PUSH1), Frame::new(FRAME_PUSH2)];
     let anim = SpriteAnimation::new_movement_based(frames, true);
     let sprite = Sprite::new(anim, true);
 
-    Object::new(11, String::from("Golf GTI pushed"), vec![sprite], None)
+    let mut object = Object::new(11, String::from("Golf GTI pushed"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(GTI_SOURCE).with_author(GTI_AUTHOR)));
+    object
 }
 
-pub fn get_object_tag() -> ObjectRef {
+pub fn get_object_tag() -> Object {
     let frames = vec![
         Frame::new(FRAME_TAG1),
         Frame::new(FRAME_TAG2),
@@ -49,10 +58,12 @@ pub fn get_object_tag() -> ObjectRef {
     let anim = SpriteAnimation::new_tick_based(frames, 20, true, None, false, false);
     let sprite = Sprite::new(anim, true);
 
-    Object::new(12, String::from("Golf GTI tagged"), vec![sprite], None)
+    let mut object = Object::new(12, String::from("Golf GTI tagged"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(GTI_SOURCE).with_author(GTI_AUTHOR)));
+    object
 }
 
-pub fn get_object_commit() -> ObjectRef {
+pub fn get_object_commit() -> Object {
     let frames = vec![
         Frame::new(FRAME_COMMIT1),
         Frame::new(FRAME_COMMIT2),
@@ -61,5 +72,7 @@ pub fn get_object_commit() -> ObjectRef {
     let anim = SpriteAnimation::new_tick_based(frames, 20, true, None, false, false);
     let sprite = Sprite::new(anim, true);
 
-    Object::new(13, String::from("Golf GTI commited"), vec![sprite], None)
+    let mut object = Object::new(13, String::from("Golf GTI commited"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(GTI_SOURCE).with_author(GTI_AUTHOR)));
+    object
 }
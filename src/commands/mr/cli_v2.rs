@@ -1,48 +1,68 @@
 use std::cell::RefCell;
+use std::io;
+use std::io::Write;
+use std::io::stderr;
 use std::rc::Rc;
+use std::time::Duration;
 
 use crate::command::CommandV2;
+use crate::command::command_args;
 use crate::commands::mr::objects::get_object_explosion;
 use crate::commands::mr::objects::get_object_mini;
 use crate::commands::mr::objects::get_object_sign_fail;
-use crate::commands::mr::objects::get_object_sign_land;
 use crate::commands::mr::objects::get_object_sign_success;
 use crate::commands::mr::objects::get_object_sign_tryagain;
 use crate::commands::mr::objects::get_object_spaceport;
 use crate::commands::mr::objects::get_object_std;
+use crate::commands::mr::objects::get_widget_best_landing;
+use crate::commands::mr::objects::get_widget_mission_clock;
+use crate::commands::mr::objects::get_widget_sign_land;
+use crate::engine_v2::arena::ObjectArena;
 use crate::engine_v2::collision::Collision;
 use crate::engine_v2::collision::ScreenEdge;
 use crate::engine_v2::coords::Coords;
+use crate::engine_v2::dialog::Prompt;
 use crate::engine_v2::engine::Engine;
+use crate::engine_v2::entity::effect::Effect;
 use crate::engine_v2::entity::movement::Movement;
 use crate::engine_v2::entity::object::Object;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::event_log::StopReason;
+use crate::engine_v2::input::KeyBinding;
 use crate::engine_v2::position::Position;
 use crate::engine_v2::position::XTermPosition;
 use crate::engine_v2::position::YTermPosition;
+use crate::engine_v2::random;
+use crate::engine_v2::replay;
+use crate::storage::Storage;
 use crate::tools::get_terminal_size;
 use crate::tools::parse_args;
 use crossterm::event::KeyCode;
 use rand::RngExt;
 
+/// Ticks for the end signs to appear.
+const SIGN_EFFECT_TICKS: usize = 150;
+
 pub struct Mr {
     pub landed: Rc<RefCell<bool>>,
     pub retry: Rc<RefCell<bool>>,
+    pub landing_time: Rc<RefCell<Option<Duration>>>,
 }
 
 impl CommandV2 for Mr {
-    fn get_all_objects(&self) -> Vec<fn() -> ObjectRef> {
+    fn get_all_objects(&self) -> Vec<fn() -> Object> {
         vec![get_object_std, get_object_mini, get_object_spaceport]
     }
     fn select_objects(
         &mut self,
         args: impl Iterator<Item = String>,
-    ) -> (Vec<ObjectRef>, Vec<Collision>) {
-        let mut rng = rand::rng();
+    ) -> (ObjectArena, Vec<Collision>) {
+        let mut rng = random::rng();
         let term_size = get_terminal_size();
         let landed_clone = self.landed.clone();
         let retry_clone = self.retry.clone();
-        let mut object_list: Vec<ObjectRef> = Vec::new();
+        let landing_time_clone = self.landing_time.clone();
+        *landing_time_clone.borrow_mut() = None;
+        let mut objects = ObjectArena::new();
         let rocket_speed: usize = 20;
 
         let short_flags = ['f', 'r'];
@@ -60,69 +80,62 @@ impl CommandV2 for Mr {
             Position::new(XTermPosition::Middle, YTermPosition::Middle, 0),
             20,
         );
-        // Land sign
-        let land_sign_object = get_object_sign_land();
-        let sign_position = Movement::new_stationary(
-            Position::new(XTermPosition::LeftIn, YTermPosition::TopIn, 0),
-            0,
-        );
-        land_sign_object
-            .borrow_mut()
-            .set_movement(sign_position.clone());
-        object_list.push(land_sign_object.clone());
         // Success sign
-        let success_sign_object = get_object_sign_success();
-        success_sign_object
-            .borrow_mut()
-            .set_movement(end_sign_position.clone());
-        success_sign_object.borrow_mut().set_visible(false);
-        object_list.push(success_sign_object.clone());
+        let mut success_sign_object = get_object_sign_success();
+        success_sign_object.set_movement(end_sign_position.clone());
+        success_sign_object.set_visible(false);
+        let success_sign = objects.insert(success_sign_object);
         // Failed sign
-        let failed_sign_object = get_object_sign_fail();
-        failed_sign_object.borrow_mut().set_visible(false);
-        failed_sign_object
-            .borrow_mut()
-            .set_movement(end_sign_position.clone());
-        object_list.push(failed_sign_object.clone());
+        let mut failed_sign_object = get_object_sign_fail();
+        failed_sign_object.set_visible(false);
+        failed_sign_object.set_movement(end_sign_position.clone());
+        let failed_sign = objects.insert(failed_sign_object);
         // Try again sign
-        let tryagain_sign_object = get_object_sign_tryagain();
-        tryagain_sign_object.borrow_mut().set_visible(false);
-        tryagain_sign_object
-            .borrow_mut()
-            .set_movement(end_sign_position.clone());
-        object_list.push(tryagain_sign_object.clone());
+        let mut tryagain_sign_object = get_object_sign_tryagain();
+        tryagain_sign_object.set_visible(false);
+        tryagain_sign_object.set_movement(end_sign_position.clone());
+        let tryagain_sign = objects.insert(tryagain_sign_object);
 
         // Rocket
-        let mut rocket_object: ObjectRef = get_object_mini();
+        let mut rocket_object = get_object_mini();
         if flags.contains("f") || flags.contains("force") {
             rocket_object = get_object_std();
         }
 
-        // Rocket control
-        Object::on_key(&rocket_object, KeyCode::Char('d'), move |o| {
-            o.borrow_mut().movement().add_offset(Coords::new(1, 0, 0))
-        });
-        Object::on_key(&rocket_object, KeyCode::Right, move |o| {
-            o.borrow_mut().movement().add_offset(Coords::new(1, 0, 0))
-        });
-        Object::on_key(&rocket_object, KeyCode::Char('a'), move |o| {
-            o.borrow_mut().movement().add_offset(Coords::new(-1, 0, 0));
-        });
-        Object::on_key(&rocket_object, KeyCode::Left, move |o| {
-            o.borrow_mut().movement().add_offset(Coords::new(-1, 0, 0))
-        });
+        // Rocket control, a tap moves the rocket by one cell
+        // and holding the key steers it smoothly
+        let steering_ticks = 10;
+        let controls = [
+            (KeyCode::Char('d'), 1),
+            (KeyCode::Right, 1),
+            (KeyCode::Char('a'), -1),
+            (KeyCode::Left, -1),
+        ];
+        for (key, dx) in controls {
+            rocket_object.on_key(key, move |rocket, engine| {
+                engine.objects_mut()[rocket]
+                    .movement_mut()
+                    .add_offset(Coords::new(dx, 0, 0));
+            });
+            rocket_object.on_key_binding(KeyBinding::held(key), move |rocket, engine| {
+                if engine.tick_id() % steering_ticks == 0 {
+                    engine.objects_mut()[rocket]
+                        .movement_mut()
+                        .add_offset(Coords::new(dx, 0, 0));
+                }
+            });
+        }
         let movement = Movement::new_linear(
             Position::new(XTermPosition::Middle, YTermPosition::TopOut, 0),
             Position::new(XTermPosition::Middle, YTermPosition::Coord(-2), 0),
             rocket_speed as i32,
         );
-        rocket_object.borrow_mut().set_movement(movement);
-        object_list.push(rocket_object.clone());
+        rocket_object.set_movement(movement);
+        let rocket = objects.insert(rocket_object);
         // Space port
-        let spaceport_object = get_object_spaceport();
+        let mut spaceport_object = get_object_spaceport();
         let spaceport_x_position: i32 = rng.random_range(
-            0..(term_size.width() as i32)
-                - spaceport_object.borrow().current_frame().get_width() as i32,
+            0..(term_size.width() as i32) - spaceport_object.current_frame().get_width() as i32,
         );
         let spaceport_position = Movement::new_stationary(
             Position::new(
@@ -132,30 +145,29 @@ impl CommandV2 for Mr {
             ),
             300,
         );
-        spaceport_object
-            .borrow_mut()
-            .set_movement(spaceport_position);
-        object_list.push(spaceport_object.clone());
+        spaceport_object.set_movement(spaceport_position);
+        let spaceport = objects.insert(spaceport_object);
 
         // Explosion
-        let explosion_object = get_object_explosion();
-        explosion_object.borrow_mut().set_visible(false);
+        let mut explosion_object = get_object_explosion();
+        explosion_object.set_visible(false);
         let movement = Movement::new_stationary(
             Position::new(XTermPosition::LeftIn, YTermPosition::TopIn, 0),
             0,
         );
-        explosion_object.borrow_mut().set_movement(movement);
-        object_list.push(explosion_object.clone());
+        explosion_object.set_movement(movement);
+        let explosion = objects.insert(explosion_object);
 
         // Collisions
         let mut collision_list: Vec<Collision> = Vec::new();
 
         // Rocket collision with spaceport
-        let collision = Collision::new_object(
-            rocket_object.clone(),
-            spaceport_object.clone(),
-            move |rocket_object, _, counter, engine| {
-                let rocket_coord = rocket_object.borrow().coords();
+        let collision =
+            Collision::new_object(rocket, spaceport, move |rocket, _, counter, engine| {
+                let terminal_size = engine.terminal_size();
+                let objects = engine.objects_mut();
+                let rocket_object = &mut objects[rocket];
+                let rocket_coord = rocket_object.coords();
                 let rocket_landed_traj = Movement::new_stationary(
                     Position::new(
                         XTermPosition::Coord(rocket_coord.x()),
@@ -164,77 +176,130 @@ impl CommandV2 for Mr {
                     ),
                     20,
                 );
-                rocket_object.borrow_mut().set_movement(rocket_landed_traj);
-                rocket_object.borrow_mut().set_frame_id(0);
-                rocket_object
-                    .borrow_mut()
-                    .compute_predefined_path(engine.terminal_size());
-                success_sign_object.borrow_mut().set_visible(true);
+                rocket_object.set_movement(rocket_landed_traj);
+                rocket_object.set_frame_id(0);
+                rocket_object.compute_predefined_path(terminal_size);
                 *landed_clone.borrow_mut() = true;
+                if counter == 1 {
+                    objects[success_sign].set_effect(Some(Effect::fade_in(SIGN_EFFECT_TICKS)));
+                    *landing_time_clone.borrow_mut() = Some(engine.scene_time());
+                }
                 if counter >= 200 {
                     engine.stop();
                 }
-            },
-        );
+            });
         collision_list.push(collision);
 
         // Rocket Collision with bottom of the screen
         let collision_bottom = Collision::new_edge(
-            rocket_object.clone(),
+            rocket,
             ScreenEdge::Bottom,
-            move |rocket_object, counter, engine| {
-                if rocket_object.borrow().visible() && counter > rocket_speed {
-                    rocket_object.borrow_mut().set_visible(false);
-                    let rocket_coord = rocket_object.borrow_mut().coords();
+            move |rocket, counter, engine| {
+                let terminal_size = engine.terminal_size();
+                let tick_id = engine.tick_id();
+                let objects = engine.objects_mut();
+                if objects[rocket].visible() && counter > rocket_speed {
+                    objects[rocket].set_visible(false);
+                    let rocket_coord = objects[rocket].coords();
                     let explosion_position = Movement::new_stationary(
                         Position::new(
                             XTermPosition::Coord(
                                 rocket_coord.x()
-                                    - (explosion_object.borrow().current_frame().get_width() / 2)
-                                        as i32
-                                    + (rocket_object.borrow().current_frame().get_width() / 2)
-                                        as i32,
+                                    - (objects[explosion].current_frame().get_width() / 2) as i32
+                                    + (objects[rocket].current_frame().get_width() / 2) as i32,
                             ),
                             YTermPosition::Coord(0),
                             0,
                         ),
                         0,
                     );
-                    explosion_object
-                        .borrow_mut()
-                        .set_movement(explosion_position);
-                    explosion_object
-                        .borrow_mut()
-                        .compute_predefined_path(engine.terminal_size());
-                    explosion_object.borrow_mut().set_visible(true);
-                    explosion_object
-                        .borrow_mut()
-                        .reset_animation(engine.tick_id());
+                    let explosion_object = &mut objects[explosion];
+                    explosion_object.set_movement(explosion_position);
+                    explosion_object.compute_predefined_path(terminal_size);
+                    explosion_object.set_visible(true);
+                    explosion_object.reset_animation(tick_id);
 
-                    if is_recursive {
-                        tryagain_sign_object.borrow_mut().set_visible(true);
-                    } else {
-                        failed_sign_object.borrow_mut().set_visible(true);
-                    }
+                    let sign = match is_recursive {
+                        true => tryagain_sign,
+                        false => failed_sign,
+                    };
+                    objects[sign].set_effect(Some(Effect::dissolve_in(SIGN_EFFECT_TICKS)));
                 } else if counter > 500 {
                     engine.stop();
                 }
             },
         );
         collision_list.push(collision_bottom);
-        (object_list, collision_list)
+        (objects, collision_list)
     }
 
     fn execute(&mut self) {
+        let args: Vec<String> = command_args().collect();
+        let (flags, _) = parse_args(args.clone(), &['i'], &["interactive"], &[]);
+        let mut interactive = flags.contains("i") || flags.contains("interactive");
+        let question = match args.iter().skip(1).find(|arg| !arg.starts_with('-')) {
+            Some(file) => format!("remove '{}'?", file),
+            None => String::from("remove this file?"),
+        };
+        // Launch statistics, the game still runs when they can't be read
+        let mut storage = match self.storage() {
+            Ok(storage) => Some(storage),
+            Err(error) => {
+                let _ = writeln!(
+                    stderr(),
+                    "{}: cannot read statistics: {}",
+                    self.name(),
+                    error
+                );
+                None
+            }
+        };
         loop {
-            let (objects, collisions) = self.select_objects(std::env::args());
+            let (objects, collisions) = self.select_objects(args.clone().into_iter());
             let mut engine = Engine::new(objects, collisions, 0);
+            engine.hud_mut().add(get_widget_sign_land());
+            engine.hud_mut().add(get_widget_mission_clock());
+            let best = storage
+                .as_ref()
+                .and_then(|storage| storage.get_parsed::<f64>("best_landing_time"));
+            if let Some(best) = best {
+                engine.hud_mut().add(get_widget_best_landing(best));
+            }
+            if interactive {
+                // Only ask once, retries go straight to the launch
+                interactive = false;
+                let retry_clone = self.retry.clone();
+                engine.open_dialog(Prompt::new(question.clone(), move |answer, engine| {
+                    if !answer {
+                        *retry_clone.borrow_mut() = false;
+                        engine.stop();
+                    }
+                }));
+            }
             engine.run();
 
+            // A replay doesn't count as a new launch
+            let launched = engine.tick_id() > 0 && !replay::is_replaying();
+            if let Some(storage) = storage.as_mut().filter(|_| launched) {
+                let landing_time = *self.landing_time.borrow();
+                if let Err(error) = save_statistics(storage, landing_time, best) {
+                    let _ = writeln!(
+                        stderr(),
+                        "{}: cannot save statistics: {}",
+                        self.name(),
+                        error
+                    );
+                }
+            }
+
             if engine.is_killed() {
                 // SIG KILL
                 break;
             }
+            if engine.stop_reason() == Some(StopReason::MaxDuration) {
+                // Out of time, no new launch
+                break;
+            }
             if !*self.retry.borrow() {
                 // recursive
                 break;
@@ -247,6 +312,23 @@ impl CommandV2 for Mr {
     }
 }
 
+/// Counts the launch and the landing, and keeps the best landing time.
+fn save_statistics(
+    storage: &mut Storage,
+    landing_time: Option<Duration>,
+    best: Option<f64>,
+) -> io::Result<()> {
+    storage.increment("launches")?;
+    if let Some(landing_time) = landing_time {
+        storage.increment("landings")?;
+        let landing_time = landing_time.as_secs_f64();
+        if best.is_none_or(|best| landing_time < best) {
+            storage.set("best_landing_time", landing_time)?;
+        }
+    }
+    storage.save()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,14 +339,16 @@ mod tests {
         let mut mr = Mr {
             landed: Rc::new(RefCell::new(false)),
             retry: Rc::new(RefCell::new(false)),
+            landing_time: Rc::new(RefCell::new(None)),
         };
         let args: Vec<String> = vec![String::from("mr")];
         let (objects, collisions) = mr.select_objects(args.into_iter());
+        let handles = objects.handles();
 
-        assert_eq!(objects.len(), 7);
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 0);
-        assert_eq!(objects[4].borrow_mut().tdid(), 14);
-        assert_eq!(objects[5].borrow_mut().tdid(), 18);
+        assert_eq!(objects.len(), 6);
+        assert_eq!(objects[handles[0]].movement().speed(), 0);
+        assert_eq!(objects[handles[3]].tdid(), 14);
+        assert_eq!(objects[handles[4]].tdid(), 18);
         assert_eq!(collisions.len(), 2);
     }
 
@@ -273,14 +357,16 @@ mod tests {
         let mut mr = Mr {
             landed: Rc::new(RefCell::new(false)),
             retry: Rc::new(RefCell::new(false)),
+            landing_time: Rc::new(RefCell::new(None)),
         };
         let args: Vec<String> = vec![String::from("mr"), String::from("-f")];
         let (objects, collisions) = mr.select_objects(args.into_iter());
+        let handles = objects.handles();
 
-        assert_eq!(objects.len(), 7);
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 0);
-        assert_eq!(objects[4].borrow_mut().tdid(), 16);
-        assert_eq!(objects[5].borrow_mut().tdid(), 18);
+        assert_eq!(objects.len(), 6);
+        assert_eq!(objects[handles[0]].movement().speed(), 0);
+        assert_eq!(objects[handles[3]].tdid(), 16);
+        assert_eq!(objects[handles[4]].tdid(), 18);
         assert_eq!(collisions.len(), 2);
     }
 
@@ -289,13 +375,15 @@ mod tests {
         let mut mr = Mr {
             landed: Rc::new(RefCell::new(false)),
             retry: Rc::new(RefCell::new(true)),
+            landing_time: Rc::new(RefCell::new(None)),
         };
         let args: Vec<String> = vec![String::from("mr"), String::from("-r")];
         let (objects, collisions) = mr.select_objects(args.into_iter());
+        let handles = objects.handles();
 
-        assert_eq!(objects.len(), 7);
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 0);
-        assert_eq!(objects[3].borrow_mut().tdid(), 23);
+        assert_eq!(objects.len(), 6);
+        assert_eq!(objects[handles[0]].movement().speed(), 0);
+        assert_eq!(objects[handles[2]].tdid(), 23);
         assert_eq!(collisions.len(), 2);
     }
 }
@@ -1,10 +1,13 @@
 use crate::engine_v2::collision::Collider;
 use crate::engine_v2::coords::Coords;
+use crate::engine_v2::entity::credits::Credits;
 use crate::engine_v2::entity::frame::Frame;
 use crate::engine_v2::entity::object::Object;
-use crate::engine_v2::entity::object::ObjectRef;
 use crate::engine_v2::entity::sprite::Sprite;
 use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
+use crate::engine_v2::entity::trail::Trail;
+use crate::engine_v2::hud::Anchor;
+use crate::engine_v2::hud::HudWidget;
 use crate::engine_v2::size::Size;
 
 const FRAME_MINI1: &str = include_str!("./frames/mini1.adoc");
@@ -29,7 +32,10 @@ const FRAME_EXPLOSION8: &str = include_str!("frames/explosion8.adoc");
 const FRAME_EXPLOSION9: &str = include_str!("frames/explosion9.adoc");
 const FRAME_EXPLOSION10: &str = include_str!("frames/explosion10.adoc");
 
-pub fn get_object_mini() -> ObjectRef {
+const ROCKETS_SOURCE: &str = "https://ascii.co.uk/art/rockets";
+const EXPLOSION_SOURCE: &str = "https://ascii.co.uk/art/explosion";
+
+pub fn get_object_mini() -> Object {
     let frames = vec![
         Frame::new(FRAME_MINI1),
         Frame::new(FRAME_MINI2),
@@ -41,17 +47,24 @@ pub fn get_object_mini() -> ObjectRef {
         Size::new(frames[0].get_width(), frames[0].get_height() - 1),
         true,
     );
+    // Exhaust left by the nose of the rocket, on the fourth line of the frames
+    let trail = Trail::new(6)
+        .from_cell(4, frames[0].get_height() as i32 - 4)
+        .with_ramp("*+:. ");
     let anim = SpriteAnimation::new_movement_based(frames, true);
     let sprite = Sprite::new(anim, true);
-    Object::new(
+    let mut object = Object::new(
         14,
         String::from("Rocket mini"),
         vec![sprite],
         Some(collider),
-    )
+    );
+    object.set_credits(Some(Credits::new(ROCKETS_SOURCE)));
+    object.set_trail(Some(trail));
+    object
 }
 
-pub fn get_object_std() -> ObjectRef {
+pub fn get_object_std() -> Object {
     let frames = vec![
         Frame::new(FRAME_STD1),
         Frame::new(FRAME_STD2),
@@ -66,16 +79,18 @@ pub fn get_object_std() -> ObjectRef {
     );
     let anim = SpriteAnimation::new_movement_based(frames, true);
     let sprite = Sprite::new(anim, true);
-    Object::new(16, String::from("Rocket"), vec![sprite], Some(collider))
+    let mut object = Object::new(16, String::from("Rocket"), vec![sprite], Some(collider));
+    object.set_credits(Some(Credits::new(ROCKETS_SOURCE)));
+    object
 }
 
-pub fn get_object_spaceport() -> ObjectRef {
+pub fn get_object_spaceport() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_SPACEPORT));
     let sprite = Sprite::new(anim, true);
     Object::new(18, String::from("Spaceport"), vec![sprite], None)
 }
 
-pub fn get_object_explosion() -> ObjectRef {
+pub fn get_object_explosion() -> Object {
     let frames = vec![
         Frame::new(FRAME_EXPLOSION1),
         Frame::new(FRAME_EXPLOSION2),
@@ -90,28 +105,40 @@ pub fn get_object_explosion() -> ObjectRef {
     ];
     let anim = SpriteAnimation::new_tick_based(frames.clone(), 40, false, None, false, false);
     let sprite = Sprite::new(anim, true);
-    Object::new(19, String::from("Explosion"), vec![sprite], None)
+    let mut object = Object::new(19, String::from("Explosion"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(EXPLOSION_SOURCE)));
+    object
 }
 
-pub fn get_object_sign_land() -> ObjectRef {
-    let anim = SpriteAnimation::new_static(Frame::new(FRAME_SIGN_LAND));
-    let sprite = Sprite::new(anim, true);
-    Object::new(20, String::from("Land rocket sign"), vec![sprite], None)
+pub fn get_widget_sign_land() -> HudWidget {
+    HudWidget::new_static(Anchor::TopLeft, FRAME_SIGN_LAND)
+}
+
+pub fn get_widget_mission_clock() -> HudWidget {
+    HudWidget::new(Anchor::TopRight, |engine| {
+        let elapsed = engine.scene_time();
+        format!("T+{:.1}s", elapsed.as_secs_f64())
+    })
+    .with_margin(1, 1)
+}
+
+pub fn get_widget_best_landing(best: f64) -> HudWidget {
+    HudWidget::new_static(Anchor::TopRight, format!("Best T+{:.1}s", best)).with_margin(1, 2)
 }
 
-pub fn get_object_sign_success() -> ObjectRef {
+pub fn get_object_sign_success() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_SIGN_SUCCESS));
     let sprite = Sprite::new(anim, true);
     Object::new(21, String::from("Landed rocket sign"), vec![sprite], None)
 }
 
-pub fn get_object_sign_fail() -> ObjectRef {
+pub fn get_object_sign_fail() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_SIGN_FAILED));
     let sprite = Sprite::new(anim, true);
     Object::new(22, String::from("Rocket crashed sign"), vec![sprite], None)
 }
 
-pub fn get_object_sign_tryagain() -> ObjectRef {
+pub fn get_object_sign_tryagain() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_SIGN_TRYAGAIN));
     let sprite = Sprite::new(anim, true);
 
@@ -1,19 +1,22 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-
 use crate::command::CommandV2;
+use crate::command::command_args;
+use crate::commands::pc::objects::TRACES_ORIGIN;
 use crate::commands::pc::objects::get_object_cachel2;
 use crate::commands::pc::objects::get_object_chipset;
 use crate::commands::pc::objects::get_object_cpu;
 use crate::commands::pc::objects::get_object_data;
 use crate::commands::pc::objects::get_object_motherboard;
 use crate::commands::pc::objects::get_object_ram;
+use crate::commands::pc::objects::get_object_traces;
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
 use crate::engine_v2::collision::Collision;
 use crate::engine_v2::coords::Coords;
 use crate::engine_v2::engine::Engine;
 use crate::engine_v2::entity::movement::Movement;
 use crate::engine_v2::entity::object::Object;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::entity::tile_map::TileMap;
+use crate::engine_v2::entity::tile_map::TileProperty;
 use crate::engine_v2::position::Position;
 use crate::engine_v2::position::XTermPosition;
 use crate::engine_v2::position::YTermPosition;
@@ -21,47 +24,69 @@ use crate::tools::parse_args;
 
 // Helper function to create a object with movement and visibility
 fn create_object(
-    mut object_list: Vec<Rc<RefCell<Object>>>,
-    get_object_fn: fn() -> ObjectRef,
+    object_list: &mut ObjectArena,
+    get_object_fn: fn() -> Object,
     start_position: Position,
     end_position: Option<Position>,
     speed: usize,
     visible: bool,
-) -> ObjectRef {
-    let object = get_object_fn();
+) -> ObjectHandle {
+    let mut object = get_object_fn();
 
     let mut movement = Movement::new_stationary(start_position, 0);
     if let Some(end_p) = end_position {
         movement = Movement::new_linear(start_position, end_p, speed as i32);
     }
-    object.borrow_mut().set_movement(movement);
-    object.borrow_mut().set_visible(visible);
-    object_list.push(object.clone());
-    object
+    object.set_movement(movement);
+    object.set_visible(visible);
+    object_list.insert(object)
+}
+
+// Helper function to move a data along the traces, from the waypoint `from` to the waypoint `to`
+fn trace_movement(traces: &TileMap, from: &str, to: &str, z: i32, speed: usize) -> Movement {
+    let waypoint = |name: &str| {
+        traces
+            .find_trigger(name)
+            .unwrap_or_else(|| panic!("no waypoint {} on the traces", name))
+    };
+    let path = traces
+        .find_path(waypoint(from), waypoint(to))
+        .unwrap_or_else(|| panic!("no trace from {} to {}", from, to));
+    let waypoints = path
+        .iter()
+        .map(|coords| {
+            Coords::new(
+                TRACES_ORIGIN.0 + coords.x(),
+                TRACES_ORIGIN.1 + coords.y(),
+                z,
+            )
+        })
+        .collect();
+    Movement::new_path(waypoints, speed as i32)
 }
 
 pub struct Pc {}
 
 impl CommandV2 for Pc {
-    fn get_all_objects(&self) -> Vec<fn() -> ObjectRef> {
+    fn get_all_objects(&self) -> Vec<fn() -> Object> {
         vec![get_object_motherboard]
     }
 
     fn select_objects(
         &mut self,
         args: impl Iterator<Item = String>,
-    ) -> (Vec<ObjectRef>, Vec<Collision>) {
+    ) -> (ObjectArena, Vec<Collision>) {
         let short_flags: &[char] = &[];
         let long_flags: &[&str] = &[];
         let params: &[&str] = &[];
         let (_, _) = parse_args(args.collect(), short_flags, long_flags, params);
         let mut collisions: Vec<Collision> = Vec::new();
-        let mut objects: Vec<ObjectRef> = Vec::new();
+        let mut objects = ObjectArena::new();
         let speed: usize = 17;
 
         // Motherboard
-        let motherboard_object = create_object(
-            objects.clone(),
+        create_object(
+            &mut objects,
             get_object_motherboard,
             Position::new(XTermPosition::Coord(2), YTermPosition::Coord(1), 100),
             None,
@@ -69,8 +94,8 @@ impl CommandV2 for Pc {
             true,
         );
         // Chipset
-        let chipsed_object = create_object(
-            objects.clone(),
+        let chipset = create_object(
+            &mut objects,
             get_object_chipset,
             Position::new(XTermPosition::Coord(62), YTermPosition::Coord(16), 100),
             None,
@@ -78,8 +103,8 @@ impl CommandV2 for Pc {
             true,
         );
         // RAM
-        let ram_object = create_object(
-            objects.clone(),
+        let ram = create_object(
+            &mut objects,
             get_object_ram,
             Position::new(XTermPosition::Coord(49), YTermPosition::Coord(10), 100),
             None,
@@ -87,177 +112,146 @@ impl CommandV2 for Pc {
             true,
         );
         // Cachel2
-        let cachel2_object = get_object_cachel2();
+        let mut cachel2_object = get_object_cachel2();
         let movement = Movement::new_stationary(
             Position::new(XTermPosition::Coord(78), YTermPosition::Coord(22), 100),
             0,
         );
-        cachel2_object.borrow_mut().set_movement(movement);
+        cachel2_object.set_movement(movement);
+        let cachel2 = objects.insert(cachel2_object);
         // CPU
-        let cpu_object = get_object_cpu();
+        let mut cpu_object = get_object_cpu();
         let movement = Movement::new_stationary(
             Position::new(XTermPosition::Coord(58), YTermPosition::Coord(23), 100),
             0,
         );
-        cpu_object.borrow_mut().set_movement(movement);
-        // DATA1
-        let data1_object = get_object_data(1);
-        let movement = Movement::new_linear(
-            Position::new(XTermPosition::Coord(36), YTermPosition::Coord(30), 11),
-            Position::new(XTermPosition::Coord(36), YTermPosition::Coord(20), 11),
-            speed as i32,
+        cpu_object.set_movement(movement);
+        objects.insert(cpu_object);
+        // Traces
+        let traces = create_object(
+            &mut objects,
+            get_object_traces,
+            Position::new(
+                XTermPosition::Coord(TRACES_ORIGIN.0),
+                YTermPosition::Coord(TRACES_ORIGIN.1),
+                100,
+            ),
+            None,
+            0,
+            false,
         );
-        data1_object.borrow_mut().set_movement(movement);
+        let traces_map = objects[traces]
+            .tile_map()
+            .cloned()
+            .expect("the traces have a tile map");
+        // DATA1
+        let mut data1_object = get_object_data(1);
+        let movement = trace_movement(&traces_map, "a", "b", 11, speed);
+        data1_object.set_movement(movement);
+        let data1 = objects.insert(data1_object);
         // DATA2
-        let data2_object = get_object_data(2);
-        data2_object.borrow_mut().set_visible(false);
-        let mut movement2 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(34), YTermPosition::Coord(22), 12),
-            Position::new(XTermPosition::Coord(66), YTermPosition::Coord(22), 12),
-            speed as i32,
-        );
+        let mut data2_object = get_object_data(2);
+        data2_object.set_visible(false);
+        let mut movement2 = trace_movement(&traces_map, "c", "d", 12, speed);
         movement2.deactivate();
-        data2_object.borrow_mut().set_movement(movement2);
+        data2_object.set_movement(movement2);
+        let data2 = objects.insert(data2_object);
         // DATA3
-        let data3_object = get_object_data(3);
-        data3_object.borrow_mut().set_visible(false);
-        let mut movement3 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(22), 13),
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(19), 13),
-            speed as i32,
-        );
+        let mut data3_object = get_object_data(3);
+        data3_object.set_visible(false);
+        let mut movement3 = trace_movement(&traces_map, "e", "f", 13, speed);
         movement3.deactivate();
-        data3_object.borrow_mut().set_movement(movement3);
+        data3_object.set_movement(movement3);
+        let data3 = objects.insert(data3_object);
         // DATA4
-        let data4_object = get_object_data(4);
-        let data4_object_id = data4_object.borrow().id();
-        data4_object.borrow_mut().set_visible(false);
-        let mut movement4 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(15), 14),
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(10), 14),
-            speed as i32,
-        );
+        let mut data4_object = get_object_data(4);
+        data4_object.set_visible(false);
+        let mut movement4 = trace_movement(&traces_map, "g", "h", 14, speed);
         movement4.deactivate();
-        data4_object.borrow_mut().set_movement(movement4);
+        data4_object.set_movement(movement4);
+        let data4 = objects.insert(data4_object);
         // DATA5
-        let data5_object = get_object_data(5);
-        let data5_object_id = data5_object.borrow().id();
-        data5_object.borrow_mut().set_visible(false);
-        let mut movement5 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(78), YTermPosition::Coord(10), 15),
-            Position::new(XTermPosition::Coord(78), YTermPosition::Coord(21), 15),
-            speed as i32,
-        );
+        let mut data5_object = get_object_data(5);
+        data5_object.set_visible(false);
+        let mut movement5 = trace_movement(&traces_map, "i", "j", 15, speed);
         movement5.deactivate();
-        data5_object.borrow_mut().set_movement(movement5);
+        data5_object.set_movement(movement5);
+        let data5 = objects.insert(data5_object);
         // DATA6
-        let data6_object = get_object_data(6);
-        let data6_object_id = data6_object.borrow().id();
-        data6_object.borrow_mut().set_visible(false);
-        let mut movement6 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(76), YTermPosition::Coord(32), 16),
-            Position::new(XTermPosition::Coord(58), YTermPosition::Coord(32), 16),
-            speed as i32,
-        );
+        let mut data6_object = get_object_data(6);
+        data6_object.set_visible(false);
+        let mut movement6 = trace_movement(&traces_map, "k", "l", 16, speed);
         movement6.deactivate();
-        data6_object.borrow_mut().set_movement(movement6);
+        data6_object.set_movement(movement6);
+        let data6 = objects.insert(data6_object);
         // DATA7
-        let data7_object = get_object_data(7);
-        data7_object.borrow_mut().set_visible(false);
-        let mut movement7 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(61), YTermPosition::Coord(32), 17),
-            Position::new(XTermPosition::Coord(61), YTermPosition::Coord(28), 17),
-            speed as i32,
-        );
+        let mut data7_object = get_object_data(7);
+        data7_object.set_visible(false);
+        let mut movement7 = trace_movement(&traces_map, "m", "n", 17, speed);
         movement7.deactivate();
-        data7_object.borrow_mut().set_movement(movement7);
+        data7_object.set_movement(movement7);
+        let data7 = objects.insert(data7_object);
         // DATA8
-        let data8_object = get_object_data(8);
-        let data8_object_id = data8_object.borrow().id();
-        data8_object.borrow_mut().set_visible(false);
-        let mut movement8 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(28), 18),
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(32), 18),
-            speed as i32,
-        );
+        let mut data8_object = get_object_data(8);
+        data8_object.set_visible(false);
+        let mut movement8 = trace_movement(&traces_map, "o", "p", 18, speed);
         movement8.deactivate();
-        data8_object.borrow_mut().set_movement(movement8);
+        data8_object.set_movement(movement8);
+        let data8 = objects.insert(data8_object);
         // DATA9
-        let data9_object = get_object_data(9);
-        data9_object.borrow_mut().set_visible(false);
-        let mut movement9 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(66), YTermPosition::Coord(32), 19),
-            Position::new(XTermPosition::Coord(78), YTermPosition::Coord(32), 19),
-            speed as i32,
-        );
+        let mut data9_object = get_object_data(9);
+        data9_object.set_visible(false);
+        let mut movement9 = trace_movement(&traces_map, "q", "r", 19, speed);
         movement9.deactivate();
-        data9_object.borrow_mut().set_movement(movement9);
+        data9_object.set_movement(movement9);
+        let data9 = objects.insert(data9_object);
         // DATA10
-        let data10_object = get_object_data(10);
-        let data10_object_id = data10_object.borrow().id();
-        data10_object.borrow_mut().set_visible(false);
-        let mut movement10 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(78), YTermPosition::Coord(21), 20),
-            Position::new(XTermPosition::Coord(78), YTermPosition::Coord(10), 20),
-            speed as i32,
-        );
+        let mut data10_object = get_object_data(10);
+        data10_object.set_visible(false);
+        let mut movement10 = trace_movement(&traces_map, "j", "i", 20, speed);
         movement10.deactivate();
-        data10_object.borrow_mut().set_movement(movement10);
+        data10_object.set_movement(movement10);
+        let data10 = objects.insert(data10_object);
         // DATA11
-        let data11_object = get_object_data(11);
-        let data11_object_id = data11_object.borrow().id();
-        data11_object.borrow_mut().set_visible(false);
-        let mut movement11 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(10), 21),
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(16), 21),
-            speed as i32,
-        );
+        let mut data11_object = get_object_data(11);
+        data11_object.set_visible(false);
+        let mut movement11 = trace_movement(&traces_map, "h", "s", 21, speed);
         movement11.deactivate();
-        data11_object.borrow_mut().set_movement(movement11);
+        data11_object.set_movement(movement11);
+        let data11 = objects.insert(data11_object);
         // DATA12
-        let data12_object = get_object_data(12);
-        let data12_object_id = data12_object.borrow().id();
-        data12_object.borrow_mut().set_visible(false);
-        let mut movement12 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(19), 22),
-            Position::new(XTermPosition::Coord(68), YTermPosition::Coord(22), 22),
-            speed as i32,
-        );
+        let mut data12_object = get_object_data(12);
+        data12_object.set_visible(false);
+        let mut movement12 = trace_movement(&traces_map, "f", "e", 22, speed);
         movement12.deactivate();
-        data12_object.borrow_mut().set_movement(movement12);
+        data12_object.set_movement(movement12);
+        let data12 = objects.insert(data12_object);
         // DATA13
-        let data13_object = get_object_data(13);
-        let data13_object_id = data13_object.borrow().id();
-        data13_object.borrow_mut().set_visible(false);
-        let mut movement13 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(65), YTermPosition::Coord(22), 23),
-            Position::new(XTermPosition::Coord(20), YTermPosition::Coord(22), 23),
-            speed as i32,
-        );
+        let mut data13_object = get_object_data(13);
+        data13_object.set_visible(false);
+        let mut movement13 = trace_movement(&traces_map, "t", "u", 23, speed);
         movement13.deactivate();
-        data13_object.borrow_mut().set_movement(movement13);
+        data13_object.set_movement(movement13);
+        let data13 = objects.insert(data13_object);
 
         // DATA14
-        let data14_object = get_object_data(14);
-        data14_object.borrow_mut().set_visible(false);
-        let mut movement14 = Movement::new_linear(
-            Position::new(XTermPosition::Coord(23), YTermPosition::Coord(20), 24),
-            Position::new(XTermPosition::Coord(23), YTermPosition::Coord(31), 24),
-            speed as i32,
-        );
+        let mut data14_object = get_object_data(14);
+        data14_object.set_visible(false);
+        let mut movement14 = trace_movement(&traces_map, "v", "cpu", 24, speed);
         movement14.deactivate();
-        data14_object.borrow_mut().set_movement(movement14);
+        data14_object.set_movement(movement14);
+        let data14 = objects.insert(data14_object);
 
         // COLLISIONS
         // Collision data1/data2
         let collision1 = Collision::new_object(
-            data1_object.clone(),
-            data2_object.clone(),
-            move |data1_objectc, data2_objectc, counter, _| {
-                data2_objectc.borrow_mut().set_visible(true);
-                data2_objectc.borrow_mut().activate_movement();
+            data1,
+            data2,
+            move |data1_objectc, data2_objectc, counter, engine| {
+                engine.objects_mut()[data2_objectc].set_visible(true);
+                engine.objects_mut()[data2_objectc].activate_movement();
                 if counter == 2 * speed {
-                    data1_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data1_objectc].set_visible(false);
                 }
             },
         );
@@ -266,244 +260,187 @@ impl CommandV2 for Pc {
 
         // Collision data2/data3
         let collision2 = Collision::new_object(
-            data2_object.clone(),
-            data3_object.clone(),
-            move |data2_objectc, data3_objectc, counter, _| {
+            data2,
+            data3,
+            move |data2_objectc, data3_objectc, counter, engine| {
                 if counter == 2 * speed {
-                    data3_objectc.borrow_mut().activate_movement();
-                    data3_objectc.borrow_mut().set_visible(true);
+                    engine.objects_mut()[data3_objectc].activate_movement();
+                    engine.objects_mut()[data3_objectc].set_visible(true);
                 }
                 if counter == 4 * speed {
-                    data2_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data2_objectc].set_visible(false);
                 }
             },
         );
         collisions.push(collision2);
 
         // Collision data3/chipset
-        let collision3 = Collision::new_object(
-            data3_object.clone(),
-            chipsed_object.clone(),
-            move |data3_objectc, _, counter, engine| {
+        let collision3 =
+            Collision::new_object(data3, chipset, move |data3_objectc, _, counter, engine| {
                 if counter == 3 * speed {
-                    data3_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data3_objectc].set_visible(false);
                 }
                 if counter == speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data4_object_id {
-                            object.borrow_mut().set_visible(true);
-                            object.borrow_mut().activate_movement();
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data4];
+                    object.set_visible(true);
+                    object.activate_movement();
                 }
-            },
-        );
+            });
         collisions.push(collision3);
 
         // Collision data4/ram
-        let collision4 = Collision::new_object(
-            data4_object.clone(),
-            ram_object.clone(),
-            move |data4_objectc, _, counter, engine| {
+        let collision4 =
+            Collision::new_object(data4, ram, move |data4_objectc, _, counter, engine| {
                 if counter == 2 * speed {
-                    data4_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data4_objectc].set_visible(false);
                 }
                 if counter == 2 * speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data5_object_id {
-                            object.borrow_mut().set_visible(true);
-                            object.borrow_mut().activate_movement();
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data5];
+                    object.set_visible(true);
+                    object.activate_movement();
                 }
-            },
-        );
+            });
         collisions.push(collision4);
 
         // Collision data5/cachel2
-        let collision5 = Collision::new_object(
-            data5_object.clone(),
-            cachel2_object.clone(),
-            move |data5_objectc, _, counter, engine| {
+        let collision5 =
+            Collision::new_object(data5, cachel2, move |data5_objectc, _, counter, engine| {
                 if counter == 3 * speed {
-                    data5_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data5_objectc].set_visible(false);
                 }
                 if counter == 2 * speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data6_object_id {
-                            object.borrow_mut().set_visible(true);
-                            object.borrow_mut().activate_movement();
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data6];
+                    object.set_visible(true);
+                    object.activate_movement();
                 }
-            },
-        );
+            });
         collisions.push(collision5);
         // Collision data6/data7
         let collision6 = Collision::new_object(
-            data6_object.clone(),
-            data7_object.clone(),
+            data6,
+            data7,
             move |data6_objectc, data7_objectc, counter, engine| {
                 if counter == 2 * speed {
-                    data7_objectc.borrow_mut().set_visible(true);
-                    data7_objectc.borrow_mut().activate_movement();
+                    engine.objects_mut()[data7_objectc].set_visible(true);
+                    engine.objects_mut()[data7_objectc].activate_movement();
                 }
                 if counter == 4 * speed {
-                    data6_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data6_objectc].set_visible(false);
                 }
                 if counter == 4 * speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data8_object_id {
-                            object.borrow_mut().set_visible(true);
-                            object.borrow_mut().activate_movement();
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data8];
+                    object.set_visible(true);
+                    object.activate_movement();
                 }
             },
         );
         collisions.push(collision6);
         // Collision data8/data9
         let collision8 = Collision::new_object(
-            data8_object.clone(),
-            data9_object.clone(),
-            move |data8_objectc, data9_objectc, counter, _| {
+            data8,
+            data9,
+            move |data8_objectc, data9_objectc, counter, engine| {
                 if counter == speed {
-                    data9_objectc.borrow_mut().set_visible(true);
-                    data9_objectc.borrow_mut().activate_movement();
+                    engine.objects_mut()[data9_objectc].set_visible(true);
+                    engine.objects_mut()[data9_objectc].activate_movement();
                 }
                 if counter == 2 * speed {
-                    data8_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data8_objectc].set_visible(false);
                 }
             },
         );
         collisions.push(collision8);
         // Collision data9/cachel2
-        let collision9 = Collision::new_object(
-            data9_object.clone(),
-            cachel2_object.clone(),
-            move |data9_objectc, _, counter, engine| {
+        let collision9 =
+            Collision::new_object(data9, cachel2, move |data9_objectc, _, counter, engine| {
                 if counter == 3 * speed {
-                    data9_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data9_objectc].set_visible(false);
                 }
                 if counter == 2 * speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data10_object_id {
-                            object.borrow_mut().set_visible(true);
-                            object.borrow_mut().activate_movement();
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data10];
+                    object.set_visible(true);
+                    object.activate_movement();
                 }
-            },
-        );
+            });
         collisions.push(collision9);
         // Collision data10/ram
-        let collision10 = Collision::new_object(
-            data10_object.clone(),
-            ram_object.clone(),
-            move |data10_objectc, _, counter, engine| {
+        let collision10 =
+            Collision::new_object(data10, ram, move |data10_objectc, _, counter, engine| {
                 if counter == 2 * speed {
-                    data10_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data10_objectc].set_visible(false);
                 }
                 if counter == 2 * speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data11_object_id {
-                            object.borrow_mut().set_visible(true);
-                            object.borrow_mut().activate_movement();
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data11];
+                    object.set_visible(true);
+                    object.activate_movement();
                 }
-            },
-        );
+            });
         collisions.push(collision10);
         // Collision data11/chipset
         let collision11 = Collision::new_object(
-            data11_object.clone(),
-            chipsed_object.clone(),
+            data11,
+            chipset,
             move |data11_objectc, _, counter, engine| {
                 if counter == 3 * speed {
-                    data11_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data11_objectc].set_visible(false);
                 }
                 if counter == speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data12_object_id {
-                            object.borrow_mut().set_visible(true);
-                            object.borrow_mut().activate_movement();
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data12];
+                    object.set_visible(true);
+                    object.activate_movement();
                 }
                 if counter == 2 * speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data13_object_id {
-                            object.borrow_mut().set_visible(true);
-                            object.borrow_mut().activate_movement();
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data13];
+                    object.set_visible(true);
+                    object.activate_movement();
                 }
                 if counter == 4 * speed {
-                    for object in engine.objects_mut() {
-                        if object.borrow().id() == data12_object_id {
-                            object.borrow_mut().set_visible(false);
-                        }
-                    }
+                    let object = &mut engine.objects_mut()[data12];
+                    object.set_visible(false);
                 }
             },
         );
         collisions.push(collision11);
         // Collision data13/data14
         let collision13 = Collision::new_object(
-            data13_object.clone(),
-            data14_object.clone(),
-            move |data13_objectc, data14_objectc, counter, _| {
+            data13,
+            data14,
+            move |data13_objectc, data14_objectc, counter, engine| {
                 if counter == speed {
-                    data14_objectc.borrow_mut().set_visible(true);
-                    data14_objectc.borrow_mut().activate_movement();
+                    engine.objects_mut()[data14_objectc].set_visible(true);
+                    engine.objects_mut()[data14_objectc].activate_movement();
                 }
                 if counter == 3 * speed {
-                    data13_objectc.borrow_mut().set_visible(false);
+                    engine.objects_mut()[data13_objectc].set_visible(false);
                 }
             },
         );
         collisions.push(collision13);
-        // Collision end
-        let end_coords = Coords::new(23, 31, 24);
-        let collision14 = Collision::new_point(
-            data14_object.clone(),
-            end_coords,
-            move |data14_objectc, _, counter, engine| {
-                if counter == 6 * speed {
-                    data14_objectc.borrow_mut().set_visible(false);
+        // Collision end, the other triggers of the traces are waypoints
+        let mut cpu_counter = 0;
+        let collision14 = Collision::new_tile(
+            data14,
+            traces,
+            TileProperty::Trigger,
+            move |data14_objectc, tile, _, _, engine| {
+                if tile.trigger_name() != Some("cpu") {
+                    return;
                 }
-                if counter == 12 * speed {
+                cpu_counter += 1;
+                if cpu_counter == 6 * speed {
+                    engine.objects_mut()[data14_objectc].set_visible(false);
+                }
+                if cpu_counter == 12 * speed {
                     engine.stop();
                 }
             },
         );
         collisions.push(collision14);
 
-        objects.push(motherboard_object);
-        objects.push(chipsed_object);
-        objects.push(ram_object);
-        objects.push(cachel2_object);
-        objects.push(cpu_object);
-        objects.push(data1_object);
-        objects.push(data2_object);
-        objects.push(data3_object);
-        objects.push(data4_object);
-        objects.push(data5_object);
-        objects.push(data6_object);
-        objects.push(data7_object);
-        objects.push(data8_object);
-        objects.push(data9_object);
-        objects.push(data10_object);
-        objects.push(data11_object);
-        objects.push(data12_object);
-        objects.push(data13_object);
-        objects.push(data14_object);
-
         (objects, collisions)
     }
     fn execute(&mut self) {
-        let (objects, collisions) = self.select_objects(std::env::args());
+        let (objects, collisions) = self.select_objects(command_args());
         let mut engine = Engine::new(objects, collisions, 0);
         engine.run()
     }
@@ -513,16 +450,20 @@ impl CommandV2 for Pc {
 mod tests {
     use super::*;
     use crate::commands::pc::cli_v2::Pc;
+    use crate::engine_v2::entity::movement::Direction;
 
     #[test]
     fn test_select_data1() {
         let mut pc = Pc {};
         let args: Vec<String> = vec![String::from("pc")];
         let (objects, collisions) = pc.select_objects(args.into_iter());
+        let handles = objects.handles();
 
-        assert_eq!(objects.len(), 19);
-        assert_eq!(objects[0].borrow_mut().movement().speed(), 0);
-        assert_eq!(objects[5].borrow_mut().movement().speed(), 17);
+        assert_eq!(objects.len(), 20);
+        assert_eq!(objects[handles[0]].movement().speed(), 0);
+        assert!(objects[handles[5]].tile_map().is_some());
+        assert_eq!(objects[handles[6]].movement().speed(), 17);
+        assert_eq!(objects[handles[6]].movement().direction(), Direction::Path);
         assert_eq!(collisions.len(), 12);
     }
 }
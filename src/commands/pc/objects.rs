@@ -1,8 +1,9 @@
 use crate::engine_v2::entity::frame::Frame;
 use crate::engine_v2::entity::object::Object;
-use crate::engine_v2::entity::object::ObjectRef;
 use crate::engine_v2::entity::sprite::Sprite;
 use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
+use crate::engine_v2::entity::tile_map::Tile;
+use crate::engine_v2::entity::tile_map::TileMap;
 
 const FRAME_MOTHERBOARD: &str = include_str!("./frames/motherboard.adoc");
 const FRAME_CHIPSET: &str = include_str!("./frames/chipset.adoc");
@@ -30,7 +31,36 @@ const FRAME_DATAL3: &str = include_str!("./frames/datal3.adoc");
 const FRAME_DATAL4: &str = include_str!("./frames/datal4.adoc");
 const FRAME_DATAL5: &str = include_str!("./frames/datal5.adoc");
 
-pub fn get_object_motherboard() -> ObjectRef {
+/// Traces of the motherboard followed by the data, the letters are the waypoints
+/// where the data start and stop, `#` is the pin of the CPU.
+const MAP_TRACES: &str = "
+                                      l--m----q-p-------k-r
+   #                                     |      |
+   |            a                        |      |
+   |            |                        |      |
+   |            |                        n      o
+   |            |
+   |            |
+   |            |
+   |            |
+   |            |
+u--+----------c-+----------------------------td-e
+   |            |                               |         j
+   v            b                               |         |
+                                                f         |
+                                                |         |
+                                                |         |
+                                                s         |
+                                                g         |
+                                                |         |
+                                                |         |
+                                                |         |
+                                                |         |
+                                                h         i";
+/// Position of the bottom left corner of the traces on the screen.
+pub const TRACES_ORIGIN: (i32, i32) = (20, 10);
+
+pub fn get_object_motherboard() -> Object {
     //let anim = AnimationOld::new_tick_based(frames, 0, 1, 50, true);
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_MOTHERBOARD));
     let sprite = Sprite::new(anim, true);
@@ -38,32 +68,48 @@ pub fn get_object_motherboard() -> ObjectRef {
     Object::new(50, String::from("Motherboard"), vec![sprite], None)
 }
 
-pub fn get_object_chipset() -> ObjectRef {
+pub fn get_object_chipset() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_CHIPSET));
     let sprite = Sprite::new(anim, true);
     //let sprite = SpriteOld::new(20, String::from("Chipset"), 100);
     Object::new(20, String::from("Chipset"), vec![sprite], None)
 }
-pub fn get_object_ram() -> ObjectRef {
+pub fn get_object_ram() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_RAM));
     let sprite = Sprite::new(anim, true);
     //let sprite = SpriteOld::new(20, String::from("RAM"), 100);
     Object::new(20, String::from("RAM"), vec![sprite], None)
 }
 
-pub fn get_object_cachel2() -> ObjectRef {
+pub fn get_object_cachel2() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_CACHEL2));
     let sprite = Sprite::new(anim, true);
     //let sprite = SpriteOld::new(20, String::from("cachel2"), 100);
     Object::new(20, String::from("Cache L2"), vec![sprite], None)
 }
-pub fn get_object_cpu() -> ObjectRef {
+pub fn get_object_cpu() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_CPU));
     let sprite = Sprite::new(anim, true);
     //let sprite = SpriteOld::new(20, String::from("CPU"), 100);
     Object::new(20, String::from("CPU"), vec![sprite], None)
 }
 
+pub fn get_tile_map_traces() -> TileMap {
+    let mut legend = vec![
+        ('-', Tile::new('─').path()),
+        ('|', Tile::new('│').path()),
+        ('+', Tile::new('┼').path()),
+        ('#', Tile::new('▣').path().trigger("cpu")),
+    ];
+    legend.extend(('a'..='v').map(|key| (key, Tile::new('•').path().trigger(&key.to_string()))));
+    TileMap::parse(MAP_TRACES, &legend).expect("invalid traces map")
+}
+
+/// The traces are drawn by the motherboard, this object only holds their tile map.
+pub fn get_object_traces() -> Object {
+    Object::new_tile_map(60, String::from("Traces"), get_tile_map_traces())
+}
+
 enum Orientation {
     Up,
     Down,
@@ -112,7 +158,7 @@ fn build_frame_list(orientation: Orientation, repeat_count: usize) -> Vec<Frame>
     }
 }
 
-pub fn get_object_data(index: usize) -> ObjectRef {
+pub fn get_object_data(index: usize) -> Object {
     let frame_list: Vec<Vec<Frame>> = vec![
         // DATA1
         build_frame_list(Orientation::Down, 7),
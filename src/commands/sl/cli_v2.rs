@@ -7,37 +7,44 @@ use crate::commands::sl::objects::get_object_d51;
 use crate::commands::sl::objects::get_object_logo;
 use crate::commands::sl::objects::get_object_logo_car;
 use crate::commands::sl::objects::get_object_logo_coal;
+use crate::commands::sl::objects::get_object_scenery_clouds;
+use crate::commands::sl::objects::get_object_scenery_ground;
+use crate::commands::sl::objects::get_object_scenery_mountains;
 use crate::commands::sl::objects::get_object_smoke;
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
 use crate::engine_v2::collision::Collision;
 use crate::engine_v2::collision::ScreenEdge;
 use crate::engine_v2::coords::Coords;
 use crate::engine_v2::engine::Engine;
 use crate::engine_v2::entity::movement::Movement;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::entity::object::Object;
 use crate::engine_v2::position::Position;
 use crate::engine_v2::position::XTermPosition;
 use crate::engine_v2::position::YTermPosition;
+use crate::tools::get_terminal_size;
 use crate::tools::parse_args;
 
 use crate::command::CommandV2;
+use crate::command::command_args;
 
 pub struct Sl {}
 
 impl CommandV2 for Sl {
-    fn get_all_objects(&self) -> Vec<fn() -> ObjectRef> {
+    fn get_all_objects(&self) -> Vec<fn() -> Object> {
         vec![get_object_d51]
     }
 
     fn select_objects(
         &mut self,
         args: impl Iterator<Item = String>,
-    ) -> (Vec<ObjectRef>, Vec<Collision>) {
-        let mut object_list: Vec<ObjectRef> = Vec::new();
+    ) -> (ObjectArena, Vec<Collision>) {
+        let mut objects = ObjectArena::new();
         // No collision
         let mut collision_list: Vec<Collision> = Vec::new();
         // Handle CLI flags
-        let short_flags = ['a', 'F', 'l', 'c'];
-        let long_flags: &[&str] = &[];
+        let short_flags = ['a', 'F', 'l', 'c', 's'];
+        let long_flags: &[&str] = &["scenery"];
         let params: &[&str] = &[];
         let (flags, _) = parse_args(args.collect(), &short_flags, long_flags, params);
         let locomotive_speed = 7;
@@ -54,8 +61,11 @@ impl CommandV2 for Sl {
             locomotive_object = get_object_c51();
         }
 
-        let locomotive_height = locomotive_object.borrow().current_frame().get_height();
-        let locomotive_width = locomotive_object.borrow().current_frame().get_width();
+        let locomotive_height = locomotive_object.current_frame().get_height();
+        let locomotive_width = locomotive_object.current_frame().get_width();
+
+        // Objects following the locomotive get their movement once its handle is known
+        let mut locomotive_children: Vec<(ObjectHandle, Coords)> = Vec::new();
 
         // Handle Coal
         let coal_x_offset = locomotive_width;
@@ -74,9 +84,9 @@ impl CommandV2 for Sl {
         } else if flags.contains("F") {
             coal_coord = Coords::new(coal_x_offset as i32 + 1, -1, 1);
         }
-        let coal_movement = Movement::new_relative(locomotive_object.clone(), coal_coord);
-        coal_object.borrow_mut().set_movement(coal_movement);
-        object_list.push(coal_object.clone());
+        let coal_width = coal_object.current_frame().get_width();
+        let coal = objects.insert(coal_object);
+        locomotive_children.push((coal, coal_coord));
 
         // Handle smoke
         let mut smoke_x_offset: u32 = 8;
@@ -84,33 +94,31 @@ impl CommandV2 for Sl {
             smoke_x_offset = 5;
         }
         let smoke_coord = Coords::new(smoke_x_offset as i32, locomotive_height as i32, 0);
-        let smoke_movement = Movement::new_relative(locomotive_object.clone(), smoke_coord);
-        smoke_object.borrow_mut().set_movement(smoke_movement);
-        object_list.push(smoke_object.clone());
+        let smoke_width = smoke_object.current_frame().get_width();
+        let smoke = objects.insert(smoke_object);
+        locomotive_children.push((smoke, smoke_coord));
 
         // Locomotive
-        let coal_width = coal_object.borrow().current_frame().get_width();
-        let smoke_width = smoke_object.borrow().current_frame().get_width();
         let mut locomotive_end_position_x =
             cmp::max(smoke_x_offset + smoke_width, coal_x_offset + coal_width);
 
         // Handle logo cars
-        let mut end_object = coal_object.clone();
+        let mut end_object = coal;
         if flags.contains("l") {
-            let car1_object = get_object_logo_car();
-            let car1_width = car1_object.borrow().current_frame().get_width();
+            let mut car1_object = get_object_logo_car();
+            let car1_width = car1_object.current_frame().get_width();
             let mut y_offset = 0;
             if flags.contains("F") {
                 y_offset = -2
             }
             let car1_coord = Coords::new(coal_width as i32 + 1, y_offset, 0);
-            let car1_movement = Movement::new_relative(coal_object.clone(), car1_coord);
-            car1_object.borrow_mut().set_movement(car1_movement);
-            let car2_object = get_object_logo_car();
+            car1_object.set_movement(Movement::new_relative(coal, car1_coord));
+            let car1 = objects.insert(car1_object);
+            let mut car2_object = get_object_logo_car();
             let car2_coord = Coords::new(car1_width as i32 + 1, y_offset, 0);
-            let car2_movement = Movement::new_relative(car1_object.clone(), car2_coord);
-            end_object = car2_object.clone();
-            car2_object.borrow_mut().set_movement(car2_movement);
+            car2_object.set_movement(Movement::new_relative(car1, car2_coord));
+            let car2 = objects.insert(car2_object);
+            end_object = car2;
 
             locomotive_end_position_x = cmp::max(
                 smoke_x_offset + smoke_width + car1_width * 2,
@@ -120,32 +128,22 @@ impl CommandV2 for Sl {
             if flags.contains("a") {
                 // Handle accident on locomotive
                 let accident_loco_coords = Coords::new(13, 3, 20);
-                let accident_object_loco = get_object_accident(0);
-                let accident_movement_loco =
-                    Movement::new_relative(locomotive_object.clone(), accident_loco_coords);
-                accident_object_loco
-                    .borrow_mut()
-                    .set_movement(accident_movement_loco);
-                object_list.push(accident_object_loco);
+                let accident_loco = objects.insert(get_object_accident(0));
+                locomotive_children.push((accident_loco, accident_loco_coords));
 
                 // Handle accident on cars
-                let parents = [car1_object.clone(), car2_object.clone()];
+                let parents = [car1, car2];
                 for parent in parents.iter() {
                     let accident_coords = [Coords::new(10, 3, 20), Coords::new(2, 3, 20)];
                     for (index, accident_coord) in accident_coords.iter().enumerate() {
-                        let accident_carx_1_coords = accident_coord;
-                        let accident_object_car1_1 = get_object_accident(index);
-                        let accident_movement_cart1_1 =
-                            Movement::new_relative(parent.clone(), *accident_carx_1_coords);
-                        accident_object_car1_1
-                            .borrow_mut()
-                            .set_movement(accident_movement_cart1_1);
-                        object_list.push(accident_object_car1_1);
+                        let mut accident_object_car = get_object_accident(index);
+                        let accident_movement_car =
+                            Movement::new_relative(*parent, *accident_coord);
+                        accident_object_car.set_movement(accident_movement_car);
+                        objects.insert(accident_object_car);
                     }
                 }
             }
-            object_list.push(car1_object);
-            object_list.push(car2_object);
         }
         // Complete locomotive
         let locomotive_end_position = Position::new(
@@ -164,10 +162,8 @@ impl CommandV2 for Sl {
             locomotive_end_position,
             locomotive_speed,
         );
-        locomotive_object
-            .borrow_mut()
-            .set_movement(locomotive_movement);
-        object_list.push(locomotive_object.clone());
+        locomotive_object.set_movement(locomotive_movement);
+        let locomotive = objects.insert(locomotive_object);
 
         // Handle accident
         if flags.contains("a") && !flags.contains("l") {
@@ -176,16 +172,45 @@ impl CommandV2 for Sl {
                 accidents_coords = vec![Coords::new(48, 6, 20), Coords::new(44, 6, 20)];
             }
             for (index, accident_coord) in accidents_coords.iter().enumerate() {
-                let accident_object = get_object_accident(index);
-                let accident_movement =
-                    Movement::new_relative(locomotive_object.clone(), *accident_coord);
-                accident_object.borrow_mut().set_movement(accident_movement);
-                object_list.push(accident_object);
+                let mut accident_object = get_object_accident(index);
+                accident_object.set_movement(Movement::new_relative(locomotive, *accident_coord));
+                objects.insert(accident_object);
+            }
+        }
+
+        // Handle scenery
+        if flags.contains("s") || flags.contains("scenery") {
+            let terminal_height = get_terminal_size().height() as i32;
+            // Same as the locomotive vertical position, in the middle
+            let locomotive_y = (terminal_height - locomotive_height as i32) / 2;
+            let layers = [
+                (
+                    get_object_scenery_clouds(locomotive),
+                    terminal_height - 4,
+                    -30,
+                ),
+                (
+                    get_object_scenery_mountains(locomotive),
+                    locomotive_y + locomotive_height as i32 - 2,
+                    -20,
+                ),
+                (get_object_scenery_ground(locomotive), locomotive_y - 2, -10),
+            ];
+            for (mut layer_object, y, z) in layers {
+                layer_object.set_movement(Movement::new_stationary(
+                    Position::new(XTermPosition::Coord(0), YTermPosition::Coord(y), z),
+                    0,
+                ));
+                objects.insert(layer_object);
             }
         }
 
+        for (child, coord) in locomotive_children {
+            objects[child].set_movement(Movement::new_relative(locomotive, coord));
+        }
+
         let collision = Collision::new_edge(
-            end_object.clone(),
+            end_object,
             ScreenEdge::LeftWithObjectRightSide,
             move |_, _, engine| {
                 engine.stop();
@@ -193,11 +218,11 @@ impl CommandV2 for Sl {
         );
         collision_list.push(collision);
 
-        (object_list, collision_list)
+        (objects, collision_list)
     }
     fn execute(&mut self) {
         // Start rendering
-        let (objects, collisions) = self.select_objects(std::env::args());
+        let (objects, collisions) = self.select_objects(command_args());
         let mut engine = Engine::new(objects, collisions, 0);
         engine.run();
     }
@@ -213,21 +238,37 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 3);
-        assert_eq!(objects[2].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[2].borrow_mut().tdid(), 1);
+        assert_eq!(objects[handles[2]].movement().speed(), 7);
+        assert_eq!(objects[handles[2]].tdid(), 1);
         assert_eq!(collisions.len(), 1);
     }
+    #[test]
+    fn test_select_scenery() {
+        let mut sl = Sl {};
+        let args: Vec<String> = vec![String::from("sl"), String::from("--scenery")];
+        let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
+
+        assert_eq!(objects.len(), 6);
+        assert_eq!(objects[handles[2]].tdid(), 1);
+        assert_eq!(objects[handles[3]].tdname(), "Clouds");
+        assert!(!objects[handles[5]].collider().is_active());
+        assert_eq!(collisions.len(), 1);
+    }
+
     #[test]
     fn test_select_object_accident() {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-a")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 5);
-        assert_eq!(objects[2].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[2].borrow_mut().tdid(), 1);
+        assert_eq!(objects[handles[2]].movement().speed(), 7);
+        assert_eq!(objects[handles[2]].tdid(), 1);
         assert_eq!(collisions.len(), 1);
     }
 
@@ -236,10 +277,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-al")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 10);
-        assert_eq!(objects[9].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[9].borrow_mut().tdid(), 2);
+        assert_eq!(objects[handles[9]].movement().speed(), 7);
+        assert_eq!(objects[handles[9]].tdid(), 2);
         assert_eq!(collisions.len(), 1);
     }
 
@@ -248,10 +290,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-aF")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 5);
-        assert_eq!(objects[2].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[2].borrow_mut().tdid(), 1);
+        assert_eq!(objects[handles[2]].movement().speed(), 7);
+        assert_eq!(objects[handles[2]].tdid(), 1);
         assert_eq!(collisions.len(), 1);
     }
 
@@ -260,10 +303,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-aFl")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 10);
-        assert_eq!(objects[9].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[9].borrow_mut().tdid(), 2);
+        assert_eq!(objects[handles[9]].movement().speed(), 7);
+        assert_eq!(objects[handles[9]].tdid(), 2);
         assert_eq!(collisions.len(), 1);
     }
 
@@ -272,10 +316,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-ac")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 5);
-        assert_eq!(objects[2].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[2].borrow_mut().tdid(), 3);
+        assert_eq!(objects[handles[2]].movement().speed(), 7);
+        assert_eq!(objects[handles[2]].tdid(), 3);
         assert_eq!(collisions.len(), 1);
     }
 
@@ -284,10 +329,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-aFc")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 5);
-        assert_eq!(objects[2].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[2].borrow_mut().tdid(), 3);
+        assert_eq!(objects[handles[2]].movement().speed(), 7);
+        assert_eq!(objects[handles[2]].tdid(), 3);
         assert_eq!(collisions.len(), 1);
     }
 
@@ -296,10 +342,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-l")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 5);
-        assert_eq!(objects[4].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[4].borrow_mut().tdid(), 2);
+        assert_eq!(objects[handles[4]].movement().speed(), 7);
+        assert_eq!(objects[handles[4]].tdid(), 2);
         assert_eq!(collisions.len(), 1);
     }
     #[test]
@@ -307,10 +354,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-c")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 3);
-        assert_eq!(objects[2].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[2].borrow_mut().tdid(), 3);
+        assert_eq!(objects[handles[2]].movement().speed(), 7);
+        assert_eq!(objects[handles[2]].tdid(), 3);
         assert_eq!(collisions.len(), 1);
     }
 
@@ -319,10 +367,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-lF")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 5);
-        assert_eq!(objects[4].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[4].borrow_mut().tdid(), 2);
+        assert_eq!(objects[handles[4]].movement().speed(), 7);
+        assert_eq!(objects[handles[4]].tdid(), 2);
         assert_eq!(collisions.len(), 1);
     }
     #[test]
@@ -330,10 +379,11 @@ mod tests {
         let mut sl = Sl {};
         let args: Vec<String> = vec![String::from("sl"), String::from("-cF")];
         let (objects, collisions) = sl.select_objects(args.into_iter());
+        let handles = objects.handles();
 
         assert_eq!(objects.len(), 3);
-        assert_eq!(objects[2].borrow_mut().movement().speed(), 7);
-        assert_eq!(objects[2].borrow_mut().tdid(), 3);
+        assert_eq!(objects[handles[2]].movement().speed(), 7);
+        assert_eq!(objects[handles[2]].tdid(), 3);
         assert_eq!(collisions.len(), 1);
     }
 }
@@ -1,6 +1,10 @@
+use crate::engine_v2::arena::ObjectHandle;
+use crate::engine_v2::entity::credits::Credits;
 use crate::engine_v2::entity::frame::Frame;
 use crate::engine_v2::entity::object::Object;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::entity::parallax::ParallaxLayer;
+use crate::engine_v2::entity::parallax::Scroll;
+use crate::engine_v2::entity::parallax::ScrollAxis;
 use crate::engine_v2::entity::sprite::Sprite;
 use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
 
@@ -31,8 +35,14 @@ const FRAME_ACCIDENT_2: &str = include_str!("frames/accident_02.adoc");
 const FRAME_COAL: &str = include_str!("frames/coal.adoc");
 const FRAME_LOGO_COAL: &str = include_str!("frames/logo_coal.adoc");
 const FRAME_LOGO_CAR: &str = include_str!("frames/logo_car.adoc");
+const FRAME_SCENERY_CLOUDS: &str = include_str!("frames/scenery_clouds.adoc");
+const FRAME_SCENERY_MOUNTAINS: &str = include_str!("frames/scenery_mountains.adoc");
+const FRAME_SCENERY_GROUND: &str = include_str!("frames/scenery_ground.adoc");
 
-pub fn get_object_d51() -> ObjectRef {
+const SL_SOURCE: &str = "https://github.com/mtoyoda/sl";
+const SL_AUTHOR: &str = "Toyoda Masashi";
+
+pub fn get_object_d51() -> Object {
     let frames = vec![
         Frame::new(FRAME_D51_01),
         Frame::new(FRAME_D51_02),
@@ -44,10 +54,12 @@ pub fn get_object_d51() -> ObjectRef {
     let anim = SpriteAnimation::new_movement_based(frames.clone(), true);
     let sprite = Sprite::new(anim, true);
 
-    Object::new(1, String::from("Steam locomotive D51"), vec![sprite], None)
+    let mut object = Object::new(1, String::from("Steam locomotive D51"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(SL_SOURCE).with_author(SL_AUTHOR)));
+    object
 }
 
-pub fn get_object_logo() -> ObjectRef {
+pub fn get_object_logo() -> Object {
     let frames = vec![
         Frame::new(FRAME_LOGO_1),
         Frame::new(FRAME_LOGO_2),
@@ -60,10 +72,12 @@ pub fn get_object_logo() -> ObjectRef {
     let anim = SpriteAnimation::new_movement_based(frames.clone(), true);
     let sprite = Sprite::new(anim, true);
 
-    Object::new(2, String::from("Steam locomotive Logo"), vec![sprite], None)
+    let mut object = Object::new(2, String::from("Steam locomotive Logo"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(SL_SOURCE).with_author(SL_AUTHOR)));
+    object
 }
 
-pub fn get_object_c51() -> ObjectRef {
+pub fn get_object_c51() -> Object {
     let frames = vec![
         Frame::new(FRAME_C51_1),
         Frame::new(FRAME_C51_2),
@@ -75,10 +89,12 @@ pub fn get_object_c51() -> ObjectRef {
 
     let anim = SpriteAnimation::new_movement_based(frames.clone(), true);
     let sprite = Sprite::new(anim, true);
-    Object::new(3, String::from("Steam locomotive C51"), vec![sprite], None)
+    let mut object = Object::new(3, String::from("Steam locomotive C51"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(SL_SOURCE).with_author(SL_AUTHOR)));
+    object
 }
 
-pub fn get_object_smoke() -> ObjectRef {
+pub fn get_object_smoke() -> Object {
     let frames = vec![
         Frame::new(FRAME_SMOKE_1),
         Frame::new(FRAME_SMOKE_2),
@@ -88,10 +104,12 @@ pub fn get_object_smoke() -> ObjectRef {
 
     let anim = SpriteAnimation::new_tick_based(frames.clone(), 20, true, None, false, false);
     let sprite = Sprite::new(anim, true);
-    Object::new(4, String::from("Smoke"), vec![sprite], None)
+    let mut object = Object::new(4, String::from("Smoke"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(SL_SOURCE).with_author(SL_AUTHOR)));
+    object
 }
 
-pub fn get_object_accident(start_frame_id: usize) -> ObjectRef {
+pub fn get_object_accident(start_frame_id: usize) -> Object {
     let frames = vec![
         Frame::new(FRAME_ACCIDENT_1),
         Frame::new(FRAME_ACCIDENT_1),
@@ -108,23 +126,59 @@ pub fn get_object_accident(start_frame_id: usize) -> ObjectRef {
         false,
     );
     let sprite = Sprite::new(anim, true);
-    Object::new(5, String::from("Accident"), vec![sprite], None)
+    let mut object = Object::new(5, String::from("Accident"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(SL_SOURCE).with_author(SL_AUTHOR)));
+    object
 }
 
-pub fn get_object_coal() -> ObjectRef {
+pub fn get_object_coal() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_COAL));
     let sprite = Sprite::new(anim, true);
-    Object::new(6, String::from("Coal"), vec![sprite], None)
+    let mut object = Object::new(6, String::from("Coal"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(SL_SOURCE).with_author(SL_AUTHOR)));
+    object
 }
 
-pub fn get_object_logo_coal() -> ObjectRef {
+pub fn get_object_logo_coal() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_LOGO_COAL));
     let sprite = Sprite::new(anim, true);
-    Object::new(7, String::from("Little coal"), vec![sprite], None)
+    let mut object = Object::new(7, String::from("Little coal"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(SL_SOURCE).with_author(SL_AUTHOR)));
+    object
 }
 
-pub fn get_object_logo_car() -> ObjectRef {
+pub fn get_object_logo_car() -> Object {
     let anim = SpriteAnimation::new_static(Frame::new(FRAME_LOGO_CAR));
     let sprite = Sprite::new(anim, true);
-    Object::new(8, String::from("Little car"), vec![sprite], None)
+    let mut object = Object::new(8, String::from("Little car"), vec![sprite], None);
+    object.set_credits(Some(Credits::new(SL_SOURCE).with_author(SL_AUTHOR)));
+    object
+}
+
+// Scenery layers scroll the other way as the locomotive moves, the farthest ones slower
+pub fn get_object_scenery_clouds(locomotive: ObjectHandle) -> Object {
+    let layer = ParallaxLayer::new(
+        Frame::new(FRAME_SCENERY_CLOUDS),
+        ScrollAxis::Horizontal,
+        Scroll::Follow(locomotive, 12),
+    );
+    Object::new_parallax(25, String::from("Clouds"), layer)
+}
+
+pub fn get_object_scenery_mountains(locomotive: ObjectHandle) -> Object {
+    let layer = ParallaxLayer::new(
+        Frame::new(FRAME_SCENERY_MOUNTAINS),
+        ScrollAxis::Horizontal,
+        Scroll::Follow(locomotive, 6),
+    );
+    Object::new_parallax(26, String::from("Mountains"), layer)
+}
+
+pub fn get_object_scenery_ground(locomotive: ObjectHandle) -> Object {
+    let layer = ParallaxLayer::new(
+        Frame::new(FRAME_SCENERY_GROUND),
+        ScrollAxis::Horizontal,
+        Scroll::Follow(locomotive, 2),
+    );
+    Object::new_parallax(27, String::from("Ground"), layer)
 }
@@ -8,21 +8,97 @@
 pub mod command;
 pub mod commands;
 pub mod engine_v2;
+pub mod storage;
 pub mod tools;
 pub mod windows_timer;
 
 #[cfg(unix)]
-mod signal {
-    const SIGINT: i32 = 2;
-    const SIG_IGN: usize = 1;
-    unsafe extern "C" {
-        unsafe fn signal(sig: i32, handler: usize) -> usize;
+pub(crate) mod signal {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::AtomicI32;
+    use std::sync::atomic::Ordering;
+
+    use libc::SIGCONT;
+    use libc::SIGHUP;
+    use libc::SIGINT;
+    use libc::SIGTERM;
+    use libc::SIGTSTP;
+    use libc::c_int;
+
+    /// Signals handled by the engine while it owns the terminal.
+    const HANDLED_SIGNALS: [c_int; 4] = [SIGHUP, SIGTERM, SIGTSTP, SIGCONT];
+
+    // Set from the signal handler, only atomics can be used there
+    static TERMINATION_SIGNAL: AtomicI32 = AtomicI32::new(0);
+    static SUSPEND_REQUESTED: AtomicBool = AtomicBool::new(false);
+    static RESUMED: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn handle_signal(sig: c_int) {
+        match sig {
+            SIGTSTP => SUSPEND_REQUESTED.store(true, Ordering::SeqCst),
+            SIGCONT => RESUMED.store(true, Ordering::SeqCst),
+            _ => TERMINATION_SIGNAL.store(sig, Ordering::SeqCst),
+        }
+    }
+
+    /// Sets the action of a signal, interrupted system calls are restarted.
+    fn set_action(sig: c_int, handler: libc::sighandler_t) {
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = handler;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(sig, &action, std::ptr::null_mut());
+        }
     }
 
     #[allow(dead_code)]
     pub fn ignore_sigint() {
+        set_action(SIGINT, libc::SIG_IGN);
+    }
+
+    /// Lets the engine handle job control and termination signals.
+    pub fn install_handlers() {
+        for sig in HANDLED_SIGNALS {
+            set_action(
+                sig,
+                handle_signal as extern "C" fn(c_int) as libc::sighandler_t,
+            );
+        }
+    }
+
+    /// Gives the signals handled by the engine their default action back.
+    pub fn restore_default_handlers() {
+        for sig in HANDLED_SIGNALS {
+            set_action(sig, libc::SIG_DFL);
+        }
+    }
+
+    /// Returns the SIGTERM or SIGHUP signal number once one was received.
+    pub fn termination_signal() -> Option<i32> {
+        match TERMINATION_SIGNAL.load(Ordering::SeqCst) {
+            0 => None,
+            sig => Some(sig),
+        }
+    }
+
+    /// Returns `true` once after a SIGTSTP was received.
+    pub fn take_suspend_request() -> bool {
+        SUSPEND_REQUESTED.swap(false, Ordering::SeqCst)
+    }
+
+    /// Returns `true` once after a SIGCONT was received.
+    pub fn take_resumed() -> bool {
+        RESUMED.swap(false, Ordering::SeqCst)
+    }
+
+    /// Stops the process like the default SIGTSTP action does,
+    /// returns when the process is continued.
+    /// The handlers must be restored to their default action first.
+    pub fn suspend() {
         unsafe {
-            signal(SIGINT, SIG_IGN);
+            libc::raise(SIGTSTP);
         }
+        SUSPEND_REQUESTED.store(false, Ordering::SeqCst);
     }
 }
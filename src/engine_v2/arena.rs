@@ -0,0 +1,253 @@
+use std::ops::Index;
+use std::ops::IndexMut;
+
+use crate::engine_v2::entity::object::Object;
+
+/// A typed handle to an object stored in an [`ObjectArena`].
+///
+/// The generation is bumped each time a slot is reused,
+/// so a handle to a removed object never points to another object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectHandle {
+    index: u32,
+    generation: u32,
+}
+
+impl ObjectHandle {
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot {
+    generation: u32,
+    object: Option<Object>,
+    // A taken object is still alive, its slot can't be reused
+    taken: bool,
+}
+
+/// A generational arena owning all the objects of a scene.
+///
+/// ```rust
+/// use coretilus::engine_v2::arena::ObjectArena;
+/// use coretilus::engine_v2::entity::frame::Frame;
+/// use coretilus::engine_v2::entity::object::Object;
+/// use coretilus::engine_v2::entity::sprite::Sprite;
+/// use coretilus::engine_v2::entity::sprite_animation::SpriteAnimation;
+///
+/// let new_object = |name: &str| {
+///     let sprite = Sprite::new(SpriteAnimation::new_static(Frame::new("o")), true);
+///     Object::new(1, String::from(name), vec![sprite], None)
+/// };
+///
+/// let mut objects = ObjectArena::new();
+/// let first = objects.insert(new_object("first"));
+/// let second = objects.insert(new_object("second"));
+/// assert_eq!(objects.len(), 2);
+/// assert_eq!(objects[second].tdname(), "second");
+///
+/// let removed = objects.remove(first).unwrap();
+/// assert_eq!(removed.tdname(), "first");
+/// assert!(objects.get(first).is_none());
+///
+/// // The slot is reused but the old handle stays invalid
+/// let third = objects.insert(new_object("third"));
+/// assert_eq!(third.index(), first.index());
+/// assert!(objects.get(first).is_none());
+/// assert_eq!(objects.handles(), vec![third, second]);
+///
+/// // Same depth, drawn in slot order
+/// assert_eq!(objects.z_ordered().collect::<Vec<_>>(), vec![third, second]);
+/// ```
+#[derive(Default)]
+pub struct ObjectArena {
+    slots: Vec<Slot>,
+    free_slots: Vec<u32>,
+    len: usize,
+    // Handles ordered by (z, index), with the last known z of each object
+    z_order: Vec<(i32, ObjectHandle)>,
+}
+
+impl ObjectArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Stores an object and returns its handle.
+    pub fn insert(&mut self, object: Object) -> ObjectHandle {
+        self.len += 1;
+        let z = object.coords().z();
+        let handle = if let Some(index) = self.free_slots.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.object = Some(object);
+            ObjectHandle {
+                index,
+                generation: slot.generation,
+            }
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                object: Some(object),
+                taken: false,
+            });
+            ObjectHandle {
+                index: self.slots.len() as u32 - 1,
+                generation: 0,
+            }
+        };
+        let position = self
+            .z_order
+            .partition_point(|entry| z_key(entry) < (z, handle.index));
+        self.z_order.insert(position, (z, handle));
+        handle
+    }
+
+    /// Removes an object from the arena, its handle becomes invalid.
+    pub fn remove(&mut self, handle: ObjectHandle) -> Option<Object> {
+        let slot = self.slot_mut(handle)?;
+        let object = slot.object.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_slots.push(handle.index);
+        self.len -= 1;
+        self.z_order.retain(|(_, other)| *other != handle);
+        Some(object)
+    }
+
+    pub fn contains(&self, handle: ObjectHandle) -> bool {
+        self.get(handle).is_some()
+    }
+
+    pub fn get(&self, handle: ObjectHandle) -> Option<&Object> {
+        self.slots
+            .get(handle.index())
+            .filter(|slot| slot.generation == handle.generation)
+            .and_then(|slot| slot.object.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: ObjectHandle) -> Option<&mut Object> {
+        self.slot_mut(handle).and_then(|slot| slot.object.as_mut())
+    }
+
+    /// Temporarily takes an object out of the arena, so it can be updated
+    /// while looking up the other objects. It must be given back with [`ObjectArena::restore`].
+    pub fn take(&mut self, handle: ObjectHandle) -> Option<Object> {
+        let slot = self.slot_mut(handle)?;
+        let object = slot.object.take()?;
+        slot.taken = true;
+        Some(object)
+    }
+
+    /// Gives back an object previously taken with [`ObjectArena::take`].
+    pub fn restore(&mut self, handle: ObjectHandle, object: Object) {
+        let slot = self
+            .slot_mut(handle)
+            .filter(|slot| slot.taken)
+            .expect("Object restored in a slot it wasn't taken from");
+        slot.object = Some(object);
+        slot.taken = false;
+    }
+
+    /// Returns the handles of all the objects, ordered by slot.
+    pub fn handles(&self) -> Vec<ObjectHandle> {
+        self.iter().map(|(handle, _)| handle).collect()
+    }
+
+    /// Returns the handles of all the objects, ordered by z-coordinate then by slot.
+    ///
+    /// The order is refreshed by [`ObjectArena::update_z_order`].
+    pub fn z_ordered(&self) -> impl Iterator<Item = ObjectHandle> + '_ {
+        self.z_order.iter().map(|(_, handle)| *handle)
+    }
+
+    /// Updates the z-order after objects moved.
+    ///
+    /// Objects rarely change depth, so the list is almost always sorted already
+    /// and an insertion sort only costs a single pass.
+    pub fn update_z_order(&mut self) {
+        for index in 0..self.z_order.len() {
+            let handle = self.z_order[index].1;
+            if let Some(object) = self.get(handle) {
+                self.z_order[index].0 = object.coords().z();
+            }
+        }
+        for index in 1..self.z_order.len() {
+            let mut position = index;
+            while position > 0
+                && z_key(&self.z_order[position]) < z_key(&self.z_order[position - 1])
+            {
+                self.z_order.swap(position, position - 1);
+                position -= 1;
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ObjectHandle, &Object)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.object.as_ref().map(|object| {
+                (
+                    ObjectHandle {
+                        index: index as u32,
+                        generation: slot.generation,
+                    },
+                    object,
+                )
+            })
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (ObjectHandle, &mut Object)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let generation = slot.generation;
+                slot.object.as_mut().map(|object| {
+                    (
+                        ObjectHandle {
+                            index: index as u32,
+                            generation,
+                        },
+                        object,
+                    )
+                })
+            })
+    }
+
+    fn slot_mut(&mut self, handle: ObjectHandle) -> Option<&mut Slot> {
+        self.slots
+            .get_mut(handle.index())
+            .filter(|slot| slot.generation == handle.generation)
+    }
+}
+
+fn z_key((z, handle): &(i32, ObjectHandle)) -> (i32, u32) {
+    (*z, handle.index)
+}
+
+impl Index<ObjectHandle> for ObjectArena {
+    type Output = Object;
+
+    fn index(&self, handle: ObjectHandle) -> &Object {
+        self.get(handle)
+            .unwrap_or_else(|| panic!("Invalid object handle: {:?}", handle))
+    }
+}
+
+impl IndexMut<ObjectHandle> for ObjectArena {
+    fn index_mut(&mut self, handle: ObjectHandle) -> &mut Object {
+        self.get_mut(handle)
+            .unwrap_or_else(|| panic!("Invalid object handle: {:?}", handle))
+    }
+}
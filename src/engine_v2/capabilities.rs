@@ -0,0 +1,209 @@
+use std::sync::OnceLock;
+
+use crate::engine_v2::graphics::GraphicsProtocol;
+
+/// Environment variable forcing the character set, `ascii` or `unicode`.
+pub const CHARSET_ENV_VAR: &str = "CORETILUS_CHARSET";
+/// Environment variable forcing the graphics protocol, `kitty`, `sixel` or `none`.
+pub const GRAPHICS_ENV_VAR: &str = "CORETILUS_GRAPHICS";
+
+static GLOBAL_CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+
+/// The colors a terminal can show, from the poorest to the richest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// What the terminal is able to draw.
+///
+/// It is guessed from the environment: `NO_COLOR`, `COLORTERM` and `TERM` for the colors,
+/// the locale and `TERM` for Unicode and the hyperlinks,
+/// `TERM` and `TERM_PROGRAM` for the graphics protocol.
+/// `CORETILUS_CHARSET` forces the character set and `CORETILUS_GRAPHICS` the graphics protocol.
+///
+/// ```rust
+/// use coretilus::engine_v2::capabilities::Capabilities;
+/// use coretilus::engine_v2::capabilities::ColorSupport;
+/// use coretilus::engine_v2::graphics::GraphicsProtocol;
+///
+/// let env = |vars: &'static [(&'static str, &'static str)]| {
+///     move |name: &str| vars.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string())
+/// };
+///
+/// let caps = Capabilities::from_vars(env(&[("TERM", "xterm-256color"), ("LANG", "en_US.UTF-8")]));
+/// assert_eq!(caps.color(), ColorSupport::Ansi256);
+/// assert!(caps.unicode());
+///
+/// let caps = Capabilities::from_vars(env(&[("TERM", "linux"), ("LANG", "en_US.UTF-8")]));
+/// assert_eq!(caps.color(), ColorSupport::Ansi16);
+/// assert!(!caps.unicode());
+/// assert!(!caps.hyperlinks());
+///
+/// let caps = Capabilities::from_vars(env(&[("COLORTERM", "truecolor"), ("NO_COLOR", "1")]));
+/// assert_eq!(caps.color(), ColorSupport::None);
+///
+/// let caps = Capabilities::from_vars(env(&[("TERM", "xterm-kitty")]));
+/// assert_eq!(caps.graphics(), Some(GraphicsProtocol::Kitty));
+/// let caps = Capabilities::from_vars(env(&[("TERM", "xterm-kitty"), ("CORETILUS_GRAPHICS", "none")]));
+/// assert_eq!(caps.graphics(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    color: ColorSupport,
+    unicode: bool,
+    graphics: Option<GraphicsProtocol>,
+    hyperlinks: bool,
+}
+
+impl Capabilities {
+    pub fn new(color: ColorSupport, unicode: bool) -> Self {
+        Self {
+            color,
+            unicode,
+            graphics: None,
+            hyperlinks: false,
+        }
+    }
+
+    pub fn with_hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    pub fn with_graphics(mut self, graphics: Option<GraphicsProtocol>) -> Self {
+        self.graphics = graphics;
+        self
+    }
+
+    /// Guesses the capabilities of the terminal from the environment variables.
+    pub fn detect() -> Self {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Guesses the capabilities from the variables returned by `var`.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+        let term = var("TERM").unwrap_or_default();
+        // Consoles with a limited font, they can't show most of Unicode
+        let basic_console = term == "linux" || term == "dumb" || term == "vt100";
+
+        let color = if var("NO_COLOR").is_some() || term == "dumb" {
+            ColorSupport::None
+        } else if var("COLORTERM").is_some_and(|value| value == "truecolor" || value == "24bit") {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else if !term.is_empty() {
+            ColorSupport::Ansi16
+        } else if cfg!(windows) {
+            ColorSupport::TrueColor
+        } else {
+            ColorSupport::None
+        };
+
+        let unicode = match var(CHARSET_ENV_VAR).as_deref() {
+            Some("ascii") => false,
+            Some("unicode") => true,
+            _ if basic_console => false,
+            _ => {
+                // The first locale variable set wins
+                let locale = ["LC_ALL", "LC_CTYPE", "LANG"].into_iter().find_map(var);
+                match locale {
+                    Some(locale) => {
+                        let locale = locale.to_lowercase();
+                        locale.contains("utf-8") || locale.contains("utf8")
+                    }
+                    None => cfg!(windows),
+                }
+            }
+        };
+
+        let term_program = var("TERM_PROGRAM").unwrap_or_default();
+        let graphics = match var(GRAPHICS_ENV_VAR).as_deref() {
+            Some("kitty") => Some(GraphicsProtocol::Kitty),
+            Some("sixel") => Some(GraphicsProtocol::Sixel),
+            Some("none") => None,
+            _ if term.contains("kitty")
+                || term == "xterm-ghostty"
+                || term_program == "WezTerm"
+                || term_program == "ghostty" =>
+            {
+                Some(GraphicsProtocol::Kitty)
+            }
+            // Sixel can't be told from TERM on xterm, it is only guessed for terminals always supporting it
+            _ if term.starts_with("foot")
+                || term.starts_with("mlterm")
+                || term == "yaft-256color" =>
+            {
+                Some(GraphicsProtocol::Sixel)
+            }
+            _ => None,
+        };
+        // OSC 8 is ignored by most terminals not supporting it, but not by the basic consoles
+        let hyperlinks = !basic_console && (!term.is_empty() || cfg!(windows));
+        Self {
+            color,
+            unicode,
+            graphics,
+            hyperlinks,
+        }
+    }
+
+    /// Returns the capabilities detected for the process, they are detected once.
+    pub fn global() -> Self {
+        *GLOBAL_CAPABILITIES.get_or_init(Self::detect)
+    }
+
+    pub fn color(&self) -> ColorSupport {
+        self.color
+    }
+
+    pub fn unicode(&self) -> bool {
+        self.unicode
+    }
+
+    /// Returns `true` when links can be made clickable with OSC 8.
+    pub fn hyperlinks(&self) -> bool {
+        self.hyperlinks
+    }
+
+    /// Returns the protocol used to draw bitmaps, `None` when only text can be drawn.
+    pub fn graphics(&self) -> Option<GraphicsProtocol> {
+        self.graphics
+    }
+}
+
+/// Returns an ASCII character looking like `char`, for terminals without Unicode.
+///
+/// ```rust
+/// use coretilus::engine_v2::capabilities::ascii_fallback;
+///
+/// let line: String = "╔═╗│▓▶⡠".chars().map(ascii_fallback).collect();
+/// assert_eq!(line, "+=+|#>.");
+/// ```
+pub fn ascii_fallback(char: char) -> char {
+    match char {
+        _ if char.is_ascii() => char,
+        '\u{a0}' => ' ',
+        '─' | '━' | '┄' | '┈' | '╌' => '-',
+        '═' => '=',
+        '│' | '┃' | '║' | '┆' | '┊' | '╎' => '|',
+        '\u{2500}'..='\u{257f}' => '+',
+        '█' | '▓' => '#',
+        '▒' => ':',
+        '░' => '.',
+        '▀' => '"',
+        '▄' => '_',
+        '▲' | '△' => '^',
+        '▼' | '▽' => 'v',
+        '◀' | '◁' => '<',
+        '▶' | '▷' => '>',
+        '\u{2800}' => ' ',
+        '\u{2801}'..='\u{28ff}' => '.',
+        _ => '?',
+    }
+}
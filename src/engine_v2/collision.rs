@@ -1,8 +1,10 @@
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
 use crate::engine_v2::coords::Coords;
 use crate::engine_v2::engine::Engine;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::entity::tile_map::Tile;
+use crate::engine_v2::entity::tile_map::TileProperty;
 use crate::engine_v2::size::Size;
-use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Collider {
@@ -40,6 +42,12 @@ impl Collider {
             + self.offset
             + Coords::new(self.size.width() as i32, self.size.height() as i32, 0)
     }
+    /// Returns `true` when `point` is inside the collider, the z-coordinate is ignored.
+    pub fn contains(&self, object_pos: Coords, point: Coords) -> bool {
+        let min = self.min(object_pos);
+        let max = self.max(object_pos);
+        (min.x()..max.x()).contains(&point.x()) && (min.y()..max.y()).contains(&point.y())
+    }
 }
 
 /// Represents the different edges of the screen that can be used for collision detection.
@@ -67,10 +75,12 @@ pub enum ScreenEdge {
     RightWithObjectLeftSide,
 }
 
-type SpriteCollisionCallback = Box<dyn FnMut(&ObjectRef, &ObjectRef, usize, &mut Engine)>;
-type EdgeCollisionCallback = Box<dyn FnMut(&ObjectRef, usize, &mut Engine)>;
-type PointCollisionCallback = Box<dyn FnMut(&ObjectRef, &Coords, usize, &mut Engine)>;
-type LineCollisionCallback = Box<dyn FnMut(&ObjectRef, i32, &LineOrientation, usize, &mut Engine)>;
+type SpriteCollisionCallback = Box<dyn FnMut(ObjectHandle, ObjectHandle, usize, &mut Engine)>;
+type EdgeCollisionCallback = Box<dyn FnMut(ObjectHandle, usize, &mut Engine)>;
+type PointCollisionCallback = Box<dyn FnMut(ObjectHandle, &Coords, usize, &mut Engine)>;
+type LineCollisionCallback =
+    Box<dyn FnMut(ObjectHandle, i32, &LineOrientation, usize, &mut Engine)>;
+type TileCollisionCallback = Box<dyn FnMut(ObjectHandle, &Tile, Coords, usize, &mut Engine)>;
 
 #[derive(Debug)]
 pub enum LineOrientation {
@@ -80,37 +90,45 @@ pub enum LineOrientation {
 
 pub enum Collision {
     Object {
-        a: ObjectRef,
-        b: ObjectRef,
+        a: ObjectHandle,
+        b: ObjectHandle,
         counter: usize,
         callback: SpriteCollisionCallback,
     },
     Edge {
-        a: ObjectRef,
+        a: ObjectHandle,
         b: ScreenEdge,
         counter: usize,
         callback: EdgeCollisionCallback,
     },
     Point {
-        a: ObjectRef,
+        a: ObjectHandle,
         c: Coords,
         counter: usize,
         callback: PointCollisionCallback,
     },
     Line {
-        a: ObjectRef,
+        a: ObjectHandle,
         c: i32,
         o: LineOrientation,
         counter: usize,
         callback: LineCollisionCallback,
     },
+    /// An object over a tile of a tile map with a property
+    Tile {
+        a: ObjectHandle,
+        map: ObjectHandle,
+        property: TileProperty,
+        counter: usize,
+        callback: TileCollisionCallback,
+    },
 }
 
 impl Collision {
     pub fn new_object(
-        a: ObjectRef,
-        b: ObjectRef,
-        callback: impl FnMut(&ObjectRef, &ObjectRef, usize, &mut Engine) + 'static,
+        a: ObjectHandle,
+        b: ObjectHandle,
+        callback: impl FnMut(ObjectHandle, ObjectHandle, usize, &mut Engine) + 'static,
     ) -> Self {
         Collision::Object {
             a,
@@ -121,9 +139,9 @@ impl Collision {
     }
 
     pub fn new_edge(
-        a: ObjectRef,
+        a: ObjectHandle,
         b: ScreenEdge,
-        callback: impl FnMut(&ObjectRef, usize, &mut Engine) + 'static,
+        callback: impl FnMut(ObjectHandle, usize, &mut Engine) + 'static,
     ) -> Self {
         Collision::Edge {
             a,
@@ -134,9 +152,9 @@ impl Collision {
     }
 
     pub fn new_point(
-        a: ObjectRef,
+        a: ObjectHandle,
         c: Coords,
-        callback: impl FnMut(&ObjectRef, &Coords, usize, &mut Engine) + 'static,
+        callback: impl FnMut(ObjectHandle, &Coords, usize, &mut Engine) + 'static,
     ) -> Self {
         Collision::Point {
             a,
@@ -147,10 +165,10 @@ impl Collision {
     }
 
     pub fn new_line(
-        a: ObjectRef,
+        a: ObjectHandle,
         c: i32,
         o: LineOrientation,
-        callback: impl FnMut(&ObjectRef, i32, &LineOrientation, usize, &mut Engine) + 'static,
+        callback: impl FnMut(ObjectHandle, i32, &LineOrientation, usize, &mut Engine) + 'static,
     ) -> Self {
         Collision::Line {
             a,
@@ -161,37 +179,71 @@ impl Collision {
         }
     }
 
+    /// Collides when the object covers a tile of the `map` object having `property`,
+    /// the callback gets the tile and its position in the scene.
+    pub fn new_tile(
+        a: ObjectHandle,
+        map: ObjectHandle,
+        property: TileProperty,
+        callback: impl FnMut(ObjectHandle, &Tile, Coords, usize, &mut Engine) + 'static,
+    ) -> Self {
+        Collision::Tile {
+            a,
+            map,
+            property,
+            counter: 0,
+            callback: Box::new(callback),
+        }
+    }
+
     pub fn counter(&self) -> usize {
         match self {
             Collision::Object { counter, .. } => *counter,
             Collision::Edge { counter, .. } => *counter,
             Collision::Point { counter, .. } => *counter,
             Collision::Line { counter, .. } => *counter,
+            Collision::Tile { counter, .. } => *counter,
+        }
+    }
+
+    /// Returns the object watched by this collision.
+    pub fn object(&self) -> ObjectHandle {
+        match self {
+            Collision::Object { a, .. } => *a,
+            Collision::Edge { a, .. } => *a,
+            Collision::Point { a, .. } => *a,
+            Collision::Line { a, .. } => *a,
+            Collision::Tile { a, .. } => *a,
         }
     }
 
-    pub fn is_colliding(&self, terminal_size: Size) -> bool {
+    /// Returns a short name describing the kind of collision.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Collision::Object { .. } => "object",
+            Collision::Edge { .. } => "edge",
+            Collision::Point { .. } => "point",
+            Collision::Line { .. } => "line",
+            Collision::Tile { .. } => "tile",
+        }
+    }
+
+    pub fn is_colliding(&self, terminal_size: Size, objects: &ObjectArena) -> bool {
         match self {
             Collision::Object { a, b, .. } => {
-                if Rc::ptr_eq(a, b) {
+                if a == b {
                     panic!("Same object twice used in the collision handler");
                 }
-                if !(a.borrow_mut().collider().is_active() && b.borrow_mut().collider().is_active())
-                {
+                let (Some(a), Some(b)) = (objects.get(*a), objects.get(*b)) else {
                     return false;
-                }
-                let (a_coords, a_collider) = {
-                    let a = a.borrow_mut();
-                    (a.coords(), *a.collider())
-                };
-                let (b_coords, b_collider) = {
-                    let b = b.borrow_mut();
-                    (b.coords(), *b.collider())
                 };
-                let a_min = a_collider.min(a_coords);
-                let b_min = b_collider.min(b_coords);
-                let a_max = a_collider.max(a_coords);
-                let b_max = b_collider.max(b_coords);
+                if !(a.collider().is_active() && b.collider().is_active()) {
+                    return false;
+                }
+                let a_min = a.collider().min(a.coords());
+                let b_min = b.collider().min(b.coords());
+                let a_max = a.collider().max(a.coords());
+                let b_max = b.collider().max(b.coords());
 
                 a_min.x() <= b_max.x()
                     && a_max.x() > b_min.x()
@@ -199,17 +251,15 @@ impl Collision {
                     && a_max.y() > b_min.y()
             }
             Collision::Edge { a, b, .. } => {
-                if !a.borrow_mut().collider().is_active() {
+                let Some(a) = objects.get(*a) else {
                     return false;
-                }
-
-                let (a_coords, a_collider) = {
-                    let a = a.borrow_mut();
-                    (a.coords(), *a.collider())
                 };
+                if !a.collider().is_active() {
+                    return false;
+                }
 
-                let a_min = a_collider.min(a_coords);
-                let a_max = a_collider.max(a_coords);
+                let a_min = a.collider().min(a.coords());
+                let a_max = a.collider().max(a.coords());
 
                 let terminal_height = terminal_size.height() as i32;
                 let terminal_width = terminal_size.width() as i32;
@@ -229,40 +279,34 @@ impl Collision {
                 }
             }
             Collision::Point { a, c, .. } => {
-                if !a.borrow_mut().collider().is_active() {
+                let Some(a) = objects.get(*a) else {
                     return false;
-                }
-                let (a_coords, a_collider) = {
-                    let a = a.borrow_mut();
-                    (a.coords(), *a.collider())
                 };
-                let a_min = a_collider.min(a_coords);
-                let a_max = a_collider.max(a_coords);
+                if !a.collider().is_active() {
+                    return false;
+                }
+                let a_min = a.collider().min(a.coords());
+                let a_max = a.collider().max(a.coords());
 
                 c.x() >= a_min.x() && c.x() <= a_max.x() && c.y() >= a_min.y() && c.y() <= a_max.y()
             }
             Collision::Line { a, c, o, .. } => {
-                if !a.borrow_mut().collider().is_active() {
+                let Some(a) = objects.get(*a) else {
                     return false;
-                }
-                let (a_coords, a_collider) = {
-                    let a = a.borrow_mut();
-                    (a.coords(), *a.collider())
                 };
-                let a_min = a_collider.min(a_coords);
-                let a_max = a_collider.max(a_coords);
-                let toto = match o {
+                if !a.collider().is_active() {
+                    return false;
+                }
+                let a_min = a.collider().min(a.coords());
+                let a_max = a.collider().max(a.coords());
+                match o {
                     LineOrientation::Horizontal => a_min.y() <= *c && a_max.y() >= *c,
                     LineOrientation::Vertical => a_min.x() <= *c && a_max.x() >= *c,
-                };
-                if false {
-                    panic!(
-                        "GGGGG min {:#?} - max {:#?} - {}- {:#?}",
-                        a_min, a_max, c, o
-                    );
                 }
-                toto
             }
+            Collision::Tile {
+                a, map, property, ..
+            } => find_tile(objects, *a, *map, *property).is_some(),
         }
     }
 
@@ -275,7 +319,7 @@ impl Collision {
                 callback,
             } => {
                 *counter += 1;
-                (callback)(a, b, *counter, engine);
+                (callback)(*a, *b, *counter, engine);
             }
             Collision::Edge {
                 a,
@@ -284,7 +328,7 @@ impl Collision {
                 ..
             } => {
                 *counter += 1;
-                (callback)(a, *counter, engine);
+                (callback)(*a, *counter, engine);
             }
             Collision::Point {
                 a,
@@ -294,7 +338,7 @@ impl Collision {
                 ..
             } => {
                 *counter += 1;
-                (callback)(a, c, *counter, engine);
+                (callback)(*a, c, *counter, engine);
             }
             Collision::Line {
                 a,
@@ -305,10 +349,52 @@ impl Collision {
                 ..
             } => {
                 *counter += 1;
-                (callback)(a, *c, o, *counter, engine);
+                (callback)(*a, *c, o, *counter, engine);
+            }
+            Collision::Tile {
+                a,
+                map,
+                property,
+                counter,
+                callback,
+            } => {
+                // The object may have left the tile since the check
+                let Some((coords, tile)) = find_tile(engine.objects(), *a, *map, *property) else {
+                    return;
+                };
+                *counter += 1;
+                (callback)(*a, &tile, coords, *counter, engine);
+            }
+        }
+    }
+}
+
+/// Returns the first tile of the map having `property` under the collider of the object,
+/// with its position in the scene.
+fn find_tile(
+    objects: &ObjectArena,
+    a: ObjectHandle,
+    map: ObjectHandle,
+    property: TileProperty,
+) -> Option<(Coords, Tile)> {
+    let (a, map) = (objects.get(a)?, objects.get(map)?);
+    let tile_map = map.tile_map()?;
+    if !a.collider().is_active() {
+        return None;
+    }
+    let a_min = a.collider().min(a.coords());
+    let a_max = a.collider().max(a.coords());
+    for y in a_min.y()..a_max.y() {
+        for x in a_min.x()..a_max.x() {
+            let (map_x, map_y) = (x - map.coords().x(), y - map.coords().y());
+            if let Some(tile) = tile_map.tile_at(map_x, map_y)
+                && tile.has(property)
+            {
+                return Some((Coords::new(x, y, map.coords().z()), tile.clone()));
             }
         }
     }
+    None
 }
 
 /*
@@ -414,3 +500,67 @@ mod tests {
     }
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_v2::entity::frame::Frame;
+    use crate::engine_v2::entity::object::Object;
+    use crate::engine_v2::entity::sprite::Sprite;
+    use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
+    use crate::engine_v2::entity::tile_map::TileMap;
+
+    /// A map at (10, 5) with a solid tile at its bottom left corner and a trigger above it,
+    /// and a single cell object.
+    fn scene(collider: Option<Collider>) -> (ObjectArena, ObjectHandle, ObjectHandle) {
+        let legend = [
+            ('X', Tile::new('X').solid()),
+            ('T', Tile::new('T').trigger("door")),
+            ('.', Tile::new('.')),
+        ];
+        let tile_map = TileMap::parse("T.\nX", &legend).unwrap();
+        let mut map = Object::new_tile_map(1, String::from("map"), tile_map);
+        map.set_coords(Coords::new(10, 5, 0));
+        let sprite = Sprite::new(SpriteAnimation::new_static(Frame::new("o")), true);
+        let object = Object::new(2, String::from("object"), vec![sprite], collider);
+        let mut objects = ObjectArena::new();
+        let map = objects.insert(map);
+        let object = objects.insert(object);
+        (objects, object, map)
+    }
+
+    #[test]
+    fn test_find_tile_hit() {
+        let (mut objects, object, map) = scene(None);
+        objects[object].set_coords(Coords::new(10, 5, 0));
+        let (coords, tile) = find_tile(&objects, object, map, TileProperty::Solid).unwrap();
+        // The position is in the scene, not in the map
+        assert_eq!(coords, Coords::new(10, 5, 0));
+        assert!(tile.is_solid());
+
+        objects[object].set_coords(Coords::new(10, 6, 0));
+        let (coords, tile) = find_tile(&objects, object, map, TileProperty::Trigger).unwrap();
+        assert_eq!(coords, Coords::new(10, 6, 0));
+        assert_eq!(tile.trigger_name(), Some("door"));
+    }
+
+    #[test]
+    fn test_find_tile_miss() {
+        let (mut objects, object, map) = scene(None);
+        // At the same position in the scene as the solid tile is in the map
+        objects[object].set_coords(Coords::new(0, 0, 0));
+        assert!(find_tile(&objects, object, map, TileProperty::Solid).is_none());
+        // Over a tile without the property
+        objects[object].set_coords(Coords::new(10, 6, 0));
+        assert!(find_tile(&objects, object, map, TileProperty::Solid).is_none());
+        // Over an empty cell of the map
+        objects[object].set_coords(Coords::new(11, 5, 0));
+        assert!(find_tile(&objects, object, map, TileProperty::Trigger).is_none());
+
+        // Without an active collider
+        let collider = Collider::new(Coords::new(0, 0, 0), Size::new(1, 1), false);
+        let (mut objects, object, map) = scene(Some(collider));
+        objects[object].set_coords(Coords::new(10, 5, 0));
+        assert!(find_tile(&objects, object, map, TileProperty::Solid).is_none());
+    }
+}
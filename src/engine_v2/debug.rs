@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::collision::Collision;
+use crate::engine_v2::coords::Coords;
+use crate::engine_v2::random;
+use crate::engine_v2::size::Size;
+
+/// Environment variable used to start the engine with the debug overlay enabled.
+pub const DEBUG_ENV_VAR: &str = "CORETILUS_DEBUG";
+
+/// A toggleable overlay drawn over the scene to help debugging.
+///
+/// It outlines each object's collider, plots its predefined path,
+/// labels it with its TypoDex id and name, lists the collision counters
+/// and shows the current tick id with the measured frame time,
+/// the number of frames dropped by the renderer and the random seed.
+///
+/// ```rust
+/// use coretilus::engine_v2::debug::DebugOverlay;
+///
+/// let mut overlay = DebugOverlay::new(false);
+/// assert!(!overlay.is_enabled());
+/// overlay.toggle();
+/// assert!(overlay.is_enabled());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DebugOverlay {
+    enabled: bool,
+    frame_time: Duration,
+    dropped_frames: usize,
+}
+
+impl DebugOverlay {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            frame_time: Duration::ZERO,
+            dropped_frames: 0,
+        }
+    }
+
+    /// Creates an overlay enabled when `CORETILUS_DEBUG` is set to a non empty value other than `0`.
+    pub fn from_env() -> Self {
+        let enabled = std::env::var(DEBUG_ENV_VAR)
+            .map(|value| !value.is_empty() && value != "0")
+            .unwrap_or(false);
+        Self::new(enabled)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    pub fn set_frame_time(&mut self, frame_time: Duration) {
+        self.frame_time = frame_time;
+    }
+
+    pub fn dropped_frames(&self) -> usize {
+        self.dropped_frames
+    }
+
+    pub fn set_dropped_frames(&mut self, dropped_frames: usize) {
+        self.dropped_frames = dropped_frames;
+    }
+
+    /// Draws the overlay on top of an already built screen.
+    pub fn draw(
+        &self,
+        screen: &mut [Vec<char>],
+        terminal_size: Size,
+        tick_id: usize,
+        objects: &ObjectArena,
+        collisions: &[Collision],
+    ) {
+        if !self.enabled {
+            return;
+        }
+        let mut canvas = DebugCanvas {
+            screen,
+            terminal_size,
+        };
+
+        for (_, object) in objects.iter() {
+            // Predefined path
+            for coords in object.predefined_path().iter() {
+                canvas.put(*coords, '.');
+            }
+            // Collider
+            let collider = object.collider();
+            if !collider.is_null() {
+                let min = collider.min(object.coords());
+                let max = collider.max(object.coords());
+                let border = if collider.is_active() { '+' } else { 'x' };
+                canvas.rectangle(min, max, border);
+            }
+            // Label
+            let label = format!("#{} {}", object.tdid(), object.tdname());
+            let label_coords = Coords::new(
+                object.coords().x(),
+                object.coords().y() + object.size().height() as i32,
+                0,
+            );
+            canvas.text(label_coords, &label);
+        }
+
+        // Status line and collision counters are written from the top of the screen
+        let top = terminal_size.height() as i32 - 1;
+        let status = format!(
+            "tick {} | frame {:.2} ms | dropped {} | objects {} | collisions {} | seed {}",
+            tick_id,
+            self.frame_time.as_secs_f64() * 1000.0,
+            self.dropped_frames,
+            objects.len(),
+            collisions.len(),
+            random::seed(),
+        );
+        canvas.text(Coords::new(0, top, 0), &status);
+        for (index, collision) in collisions.iter().enumerate() {
+            let tdid = objects
+                .get(collision.object())
+                .map(|object| object.tdid().to_string())
+                .unwrap_or(String::from("?"));
+            let line = format!(
+                "[{}] {} #{} counter {}",
+                index,
+                collision.kind(),
+                tdid,
+                collision.counter(),
+            );
+            canvas.text(Coords::new(0, top - 1 - index as i32, 0), &line);
+        }
+    }
+}
+
+/// Helper writing in the screen buffer using scene coordinates (bottom-up y axis).
+struct DebugCanvas<'a> {
+    screen: &'a mut [Vec<char>],
+    terminal_size: Size,
+}
+
+impl DebugCanvas<'_> {
+    fn put(&mut self, coords: Coords, char: char) {
+        let screen_y = self.terminal_size.height() as i32 - 1 - coords.y();
+        if screen_y < 0 || screen_y >= self.terminal_size.height() as i32 {
+            return;
+        }
+        if coords.x() < 0 || coords.x() >= self.terminal_size.width() as i32 {
+            return;
+        }
+        self.screen[screen_y as usize][coords.x() as usize] = char;
+    }
+
+    fn text(&mut self, coords: Coords, text: &str) {
+        for (dx, char) in text.chars().enumerate() {
+            self.put(Coords::new(coords.x() + dx as i32, coords.y(), 0), char);
+        }
+    }
+
+    /// Outlines the rectangle going from `min` (included) to `max` (excluded).
+    fn rectangle(&mut self, min: Coords, max: Coords, corner: char) {
+        let (left, right) = (min.x(), max.x() - 1);
+        let (bottom, top) = (min.y(), max.y() - 1);
+        for x in left..=right {
+            self.put(Coords::new(x, bottom, 0), '-');
+            self.put(Coords::new(x, top, 0), '-');
+        }
+        for y in bottom..=top {
+            self.put(Coords::new(left, y, 0), '|');
+            self.put(Coords::new(right, y, 0), '|');
+        }
+        for (x, y) in [(left, bottom), (left, top), (right, bottom), (right, top)] {
+            self.put(Coords::new(x, y, 0), corner);
+        }
+    }
+}
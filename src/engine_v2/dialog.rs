@@ -0,0 +1,243 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+
+use crate::engine_v2::engine::Engine;
+
+/// Called with the answer of a [`Prompt`].
+pub type PromptCallback = Box<dyn FnMut(bool, &mut Engine)>;
+/// Called with the selected item of a [`Menu`], `None` when cancelled.
+pub type MenuCallback = Box<dyn FnMut(Option<usize>, &mut Engine)>;
+/// Called with the text of a [`TextInput`], `None` when cancelled.
+pub type TextInputCallback = Box<dyn FnMut(Option<String>, &mut Engine)>;
+
+/// A modal widget drawn in the middle of the scene and driven by the keyboard.
+///
+/// The scene is paused while a dialog is open, key presses go to the
+/// last opened dialog instead of the objects.
+pub trait Dialog {
+    /// Handles a key press, returns `true` when the dialog is closed.
+    fn handle_key(&mut self, key: KeyEvent, engine: &mut Engine) -> bool;
+
+    /// Returns the lines of text shown in the dialog box.
+    fn lines(&self) -> Vec<String>;
+}
+
+/// A yes/no question, answered with `y` or `n`, `Esc` answers no.
+pub struct Prompt {
+    question: String,
+    on_answer: PromptCallback,
+}
+
+impl Prompt {
+    pub fn new(
+        question: impl Into<String>,
+        on_answer: impl FnMut(bool, &mut Engine) + 'static,
+    ) -> Self {
+        Self {
+            question: question.into(),
+            on_answer: Box::new(on_answer),
+        }
+    }
+}
+
+impl Dialog for Prompt {
+    fn handle_key(&mut self, key: KeyEvent, engine: &mut Engine) -> bool {
+        let answer = match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => true,
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => false,
+            _ => return false,
+        };
+        (self.on_answer)(answer, engine);
+        true
+    }
+
+    fn lines(&self) -> Vec<String> {
+        vec![format!("{} [y/n]", self.question)]
+    }
+}
+
+/// A vertical list of items, browsed with the arrows (or `j`/`k`) and selected with `Enter`.
+///
+/// The callback gets the index of the selected item, or `None` when `Esc` was pressed.
+///
+/// ```rust
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use crossterm::event::KeyCode;
+/// use crossterm::event::KeyEvent;
+/// use crossterm::event::KeyModifiers;
+/// use coretilus::engine_v2::arena::ObjectArena;
+/// use coretilus::engine_v2::dialog::Dialog;
+/// use coretilus::engine_v2::dialog::Menu;
+/// use coretilus::engine_v2::engine::Engine;
+///
+/// let mut engine = Engine::new(ObjectArena::new(), Vec::new(), 0);
+/// let selected = Rc::new(Cell::new(None));
+/// let selected_clone = selected.clone();
+/// let mut menu = Menu::new("Games", vec!["Tetris", "Quit"], move |item, _| selected_clone.set(item));
+/// assert_eq!(menu.lines(), vec!["Games", "> Tetris", "  Quit"]);
+///
+/// let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+/// assert!(!menu.handle_key(key(KeyCode::Down), &mut engine));
+/// assert!(menu.handle_key(key(KeyCode::Enter), &mut engine));
+/// assert_eq!(selected.get(), Some(1));
+/// ```
+pub struct Menu {
+    title: String,
+    items: Vec<String>,
+    selected: usize,
+    on_select: MenuCallback,
+}
+
+impl Menu {
+    pub fn new<S>(
+        title: impl Into<String>,
+        items: Vec<S>,
+        on_select: impl FnMut(Option<usize>, &mut Engine) + 'static,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            title: title.into(),
+            items: items.into_iter().map(Into::into).collect(),
+            selected: 0,
+            on_select: Box::new(on_select),
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+}
+
+impl Dialog for Menu {
+    fn handle_key(&mut self, key: KeyEvent, engine: &mut Engine) -> bool {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                false
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.items.len().saturating_sub(1));
+                false
+            }
+            KeyCode::Enter if !self.items.is_empty() => {
+                (self.on_select)(Some(self.selected), engine);
+                true
+            }
+            KeyCode::Esc => {
+                (self.on_select)(None, engine);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.title.clone()];
+        for (index, item) in self.items.iter().enumerate() {
+            let marker = if index == self.selected { '>' } else { ' ' };
+            lines.push(format!("{} {}", marker, item));
+        }
+        lines
+    }
+}
+
+/// A single line text box, submitted with `Enter`.
+///
+/// The callback gets the typed text, or `None` when `Esc` was pressed.
+pub struct TextInput {
+    label: String,
+    text: String,
+    max_length: usize,
+    on_submit: TextInputCallback,
+}
+
+impl TextInput {
+    pub fn new(
+        label: impl Into<String>,
+        max_length: usize,
+        on_submit: impl FnMut(Option<String>, &mut Engine) + 'static,
+    ) -> Self {
+        Self {
+            label: label.into(),
+            text: String::new(),
+            max_length,
+            on_submit: Box::new(on_submit),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Dialog for TextInput {
+    fn handle_key(&mut self, key: KeyEvent, engine: &mut Engine) -> bool {
+        match key.code {
+            KeyCode::Char(char) => {
+                if self.text.chars().count() < self.max_length {
+                    self.text.push(char);
+                }
+                false
+            }
+            KeyCode::Backspace => {
+                self.text.pop();
+                false
+            }
+            KeyCode::Enter => {
+                (self.on_submit)(Some(self.text.clone()), engine);
+                true
+            }
+            KeyCode::Esc => {
+                (self.on_submit)(None, engine);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let padding = self.max_length.saturating_sub(self.text.chars().count());
+        vec![
+            self.label.clone(),
+            format!("> {}_{}", self.text, " ".repeat(padding)),
+        ]
+    }
+}
+
+/// Draws the lines of a dialog in a box at the center of the screen.
+pub fn draw_dialog(screen: &mut [Vec<char>], lines: &[String]) {
+    let screen_height = screen.len() as i32;
+    let screen_width = screen.first().map(|line| line.len()).unwrap_or(0) as i32;
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let border = format!("+{}+", "-".repeat(width + 2));
+    let mut boxed = vec![border.clone()];
+    for line in lines {
+        let padding = width - line.chars().count();
+        boxed.push(format!("| {}{} |", line, " ".repeat(padding)));
+    }
+    boxed.push(border);
+
+    let top = (screen_height - boxed.len() as i32) / 2;
+    let left = (screen_width - width as i32 - 4) / 2;
+    for (dy, line) in boxed.iter().enumerate() {
+        let row = top + dy as i32;
+        if row < 0 || row >= screen_height {
+            continue;
+        }
+        for (dx, char) in line.chars().enumerate() {
+            let column = left + dx as i32;
+            if column < 0 || column >= screen_width {
+                continue;
+            }
+            screen[row as usize][column as usize] = char;
+        }
+    }
+}
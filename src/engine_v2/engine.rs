@@ -1,15 +1,25 @@
 use std::time::Duration;
 use std::time::Instant;
 
-use std::io::Write;
+use std::io::Stdout;
 use std::io::stdout;
 
 use crossterm::ExecutableCommand;
 use crossterm::cursor::Hide;
 use crossterm::cursor::Show;
+use crossterm::event::DisableMouseCapture;
+use crossterm::event::EnableMouseCapture;
 use crossterm::event::Event;
 use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use crossterm::event::KeyboardEnhancementFlags;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
+use crossterm::event::PopKeyboardEnhancementFlags;
+use crossterm::event::PushKeyboardEnhancementFlags;
 use crossterm::event::poll;
 use crossterm::event::read;
 use crossterm::terminal::EnterAlternateScreen;
@@ -17,39 +27,104 @@ use crossterm::terminal::LeaveAlternateScreen;
 use crossterm::terminal::disable_raw_mode;
 use crossterm::terminal::enable_raw_mode;
 
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
 use crate::engine_v2::collision::Collision;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::debug::DebugOverlay;
+use crate::engine_v2::dialog::Dialog;
+use crate::engine_v2::dialog::draw_dialog;
+use crate::engine_v2::entity::object::Object;
+use crate::engine_v2::entity::object::ObjectAction;
+use crate::engine_v2::event_log::EventLog;
+use crate::engine_v2::event_log::StopReason;
+use crate::engine_v2::hud::Hud;
+use crate::engine_v2::input::InputState;
+use crate::engine_v2::input::KeyTrigger;
+use crate::engine_v2::options::EngineOptions;
+use crate::engine_v2::random;
+use crate::engine_v2::renderer::Renderer;
+use crate::engine_v2::renderer::fit_screen;
+use crate::engine_v2::replay;
+use crate::engine_v2::replay::ReplayRun;
 use crate::engine_v2::scene::Scene;
 use crate::engine_v2::size::Size;
+use crate::engine_v2::weather::WeatherOverlay;
+use crate::tools::get_real_terminal_size;
 use crate::tools::get_terminal_size;
 
+#[cfg(unix)]
+use crate::signal;
+
+/// Maximum number of simulation ticks run between two renders when catching up.
+const MAX_TICKS_PER_UPDATE: usize = 250;
+/// Duration of a simulation tick at normal speed.
+const BASE_TICK_DURATION: Duration = Duration::from_millis(5);
+/// Default maximum number of frames printed per second.
+const DEFAULT_FRAME_RATE: u32 = 60;
+
 pub struct Engine {
     scene: Scene, // the scene containing all objects
     tick_duration: Duration,
+    frame_duration: Duration,
     terminal_size: Size,
     tick_id: usize,
+    // Number of simulated ticks, counted even while the scene is paused
+    step_id: usize,
     ttl: usize, // Number of tick to live, 0 means infinite
+    max_duration: Option<Duration>,
     stop_on_sigint: bool,
-    objects: Vec<ObjectRef>,
+    objects: ObjectArena,
     collisions: Vec<Collision>,
     killed: bool,
     must_stop: bool,
+    stop_reason: Option<StopReason>,
+    suspend_requested: bool,
+    mouse_capture: bool,
+    input: InputState,
+    // Start of the input clock, which advances with the steps to be the same when replaying
+    input_epoch: Instant,
+    // Events played back instead of the keyboard
+    replay: Option<ReplayRun>,
+    hud: Hud,
+    dialogs: Vec<Box<dyn Dialog>>,
+    debug: DebugOverlay,
+    // Ambient particles asked for on the command line
+    weather: Option<WeatherOverlay>,
+    log: EventLog,
 }
 
 impl Engine {
-    pub fn new(objects: Vec<ObjectRef>, collisions: Vec<Collision>, ttl: usize) -> Self {
+    pub fn new(objects: ObjectArena, collisions: Vec<Collision>, ttl: usize) -> Self {
         let terminal_size = get_terminal_size();
+        let options = EngineOptions::global();
         Self {
             scene: Scene::new(terminal_size),
-            tick_duration: Duration::from_millis(5),
+            // A faster animation runs the same ticks in less time
+            tick_duration: BASE_TICK_DURATION.div_f64(options.speed()),
+            frame_duration: Duration::from_secs(1) / DEFAULT_FRAME_RATE,
             terminal_size,
             tick_id: 0,
+            step_id: 0,
             ttl,
+            max_duration: options.max_duration(),
             stop_on_sigint: true,
-            objects: objects.to_owned(),
+            objects,
             collisions,
             killed: false,
             must_stop: false,
+            stop_reason: None,
+            suspend_requested: false,
+            mouse_capture: false,
+            input: InputState::new(),
+            input_epoch: Instant::now(),
+            replay: None,
+            hud: Hud::new(),
+            dialogs: Vec::new(),
+            debug: DebugOverlay::from_env(),
+            weather: options.weather().map(|weather| {
+                WeatherOverlay::new(weather, terminal_size, options.reduced_motion())
+            }),
+            log: EventLog::from_env(),
         }
     }
 
@@ -57,6 +132,27 @@ impl Engine {
         self.tick_id
     }
 
+    pub fn tick_duration(&self) -> Duration {
+        self.tick_duration
+    }
+
+    /// Returns the time elapsed in the scene, as if it ran at normal speed.
+    /// Unlike the wall-clock time, it doesn't depend on `--speed`.
+    pub fn scene_time(&self) -> Duration {
+        BASE_TICK_DURATION * self.tick_id as u32
+    }
+
+    /// Caps the number of frames printed per second, simulation ticks are not affected.
+    pub fn set_frame_rate(&mut self, frames_per_second: u32) {
+        self.frame_duration = Duration::from_secs(1) / frames_per_second.max(1);
+    }
+
+    /// Captures the mouse so objects receive click and hover events.
+    /// It must be set before [`Engine::run`].
+    pub fn set_mouse_capture(&mut self, enabled: bool) {
+        self.mouse_capture = enabled;
+    }
+
     pub fn terminal_size(&self) -> Size {
         self.terminal_size
     }
@@ -69,98 +165,511 @@ impl Engine {
         self.must_stop = true;
     }
 
-    pub fn objects_mut(&mut self) -> &mut Vec<ObjectRef> {
+    /// Returns why the engine stopped, `None` while it runs.
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    /// Returns the state of the keyboard, to know which keys are held.
+    pub fn input(&self) -> &InputState {
+        &self.input
+    }
+
+    pub fn hud(&self) -> &Hud {
+        &self.hud
+    }
+
+    pub fn hud_mut(&mut self) -> &mut Hud {
+        &mut self.hud
+    }
+
+    /// Opens a dialog over the scene, the scene is paused until it is closed.
+    pub fn open_dialog(&mut self, dialog: impl Dialog + 'static) {
+        self.dialogs.push(Box::new(dialog));
+    }
+
+    pub fn has_dialog(&self) -> bool {
+        !self.dialogs.is_empty()
+    }
+
+    pub fn debug_mut(&mut self) -> &mut DebugOverlay {
+        &mut self.debug
+    }
+
+    /// Draws the weather at another z-coordinate, to show it between the layers of the scene.
+    pub fn set_weather_layer(&mut self, z: i32) {
+        if let Some(weather) = &mut self.weather {
+            weather.set_layer(z);
+        }
+    }
+
+    pub fn objects(&self) -> &ObjectArena {
+        &self.objects
+    }
+
+    pub fn objects_mut(&mut self) -> &mut ObjectArena {
         &mut self.objects
     }
 
+    /// Adds an object to the running scene and returns its handle.
+    pub fn spawn(&mut self, mut object: Object) -> ObjectHandle {
+        object.compute_predefined_path(self.terminal_size);
+        self.objects.insert(object)
+    }
+
     pub fn run(&mut self) {
         let mut stdout = stdout();
-        stdout.execute(EnterAlternateScreen).unwrap(); // Go to alternate buffer
         //self.stopped = false;
-        enable_raw_mode().unwrap();
-        stdout.execute(Hide).unwrap(); // Hide cursor
+        enter_terminal(&mut stdout, self.mouse_capture);
 
-        // calculate the duration of a single tick
         self.tick_id = 0;
+        self.step_id = 0;
+        self.stop_reason = None;
+        self.replay = replay::begin_run();
+        self.log.event(
+            0,
+            "run_start",
+            &[
+                ("width", self.terminal_size.width().to_string()),
+                ("height", self.terminal_size.height().to_string()),
+                ("seed", random::seed().to_string()),
+            ],
+        );
 
-        for object in self.objects.iter() {
-            object
-                .borrow_mut()
-                .compute_predefined_path(self.terminal_size);
+        for (_, object) in self.objects.iter_mut() {
+            object.compute_predefined_path(self.terminal_size);
         }
 
-        loop {
-            let tick_start_time = Instant::now();
-            self.tick_id += 1;
-            if self.ttl > 0 && self.tick_id >= self.ttl {
+        // Frames are printed by a dedicated thread so a slow terminal doesn't stall the simulation
+        let mut renderer = Renderer::spawn(self.scene.graphics());
+        // A replay is simulated at its recorded size, the frames must fit the real terminal
+        let display_size = self.replay.is_some().then(get_real_terminal_size);
+
+        // Simulation ticks are consumed from an accumulator of elapsed wall-clock time
+        // so the animation speed doesn't depend on how fast frames are printed.
+        let mut previous_time = Instant::now();
+        let mut accumulator = Duration::ZERO;
+        // Time spent running, the time spent suspended is not counted
+        let mut running_time = Duration::ZERO;
+        let mut last_render_time: Option<Instant> = None;
+        'main: loop {
+            let now = Instant::now();
+            accumulator += now - previous_time;
+            running_time += now - previous_time;
+            previous_time = now;
+            if self
+                .max_duration
+                .is_some_and(|max_duration| running_time >= max_duration)
+            {
+                // Out of time, the run ends normally, it was not interrupted
+                self.stop_reason = Some(StopReason::MaxDuration);
                 break;
             }
 
-            if self.must_stop {
-                break;
+            let mut simulated_ticks = 0;
+            while accumulator >= self.tick_duration && !self.suspend_requested {
+                accumulator -= self.tick_duration;
+                if !self.simulate_tick() {
+                    break 'main;
+                }
+                simulated_ticks += 1;
+                if simulated_ticks >= MAX_TICKS_PER_UPDATE {
+                    // Too far behind, give up catching up instead of freezing the screen
+                    accumulator = Duration::ZERO;
+                    break;
+                }
+            }
+
+            // Job control
+            if self.suspend_requested {
+                self.suspend_requested = false;
+                renderer.sync();
+                leave_terminal(&mut stdout, self.mouse_capture);
+                #[cfg(unix)]
+                signal::suspend();
+                enter_terminal(&mut stdout, self.mouse_capture);
+                // Don't simulate the time spent suspended and redraw right away
+                previous_time = Instant::now();
+                accumulator = Duration::ZERO;
+                last_render_time = None;
+            }
+            #[cfg(unix)]
+            if signal::take_resumed() {
+                // Continued after being stopped by another signal, the screen may be stale
+                last_render_time = None;
+            }
+
+            // Render at most once per frame duration, frames are dropped when output is slow
+            let frame_due = last_render_time
+                .is_none_or(|last_render| last_render.elapsed() >= self.frame_duration);
+            if frame_due && (self.tick_id > 0 || self.has_dialog()) {
+                self.debug.set_frame_time(renderer.frame_time());
+                self.debug.set_dropped_frames(renderer.dropped_frames());
+                let placements = match self.graphics_enabled() {
+                    true => self.scene.build_placements(&self.objects),
+                    false => Vec::new(),
+                };
+                let screen = match display_size {
+                    Some(size) => fit_screen(self.build_frame(), size),
+                    None => self.build_frame(),
+                };
+                renderer.submit(screen, placements);
+                last_render_time = Some(Instant::now());
+            }
+
+            //  wait for the next tick or the next frame, whichever comes first
+            let until_next_tick = self
+                .tick_duration
+                .saturating_sub(accumulator + previous_time.elapsed());
+            let until_next_frame = last_render_time
+                .map(|last_render| self.frame_duration.saturating_sub(last_render.elapsed()))
+                .unwrap_or(until_next_tick);
+            let wait = until_next_tick.min(until_next_frame);
+            if !wait.is_zero() {
+                std::thread::sleep(wait);
             }
+        }
+
+        // Wait for the last frame before giving the terminal back
+        renderer.finish();
+        replay::end_run(self.step_id, self.killed);
+        let reason = self.stop_reason.map(|reason| reason.to_string());
+        self.log.event(
+            self.tick_id,
+            "stop",
+            &[
+                ("reason", reason.unwrap_or_default()),
+                ("killed", self.killed.to_string()),
+            ],
+        );
+        self.log.flush();
+
+        leave_terminal(&mut stdout, self.mouse_capture);
+    }
+
+    /// Runs a single simulation tick: input, scene update and collisions.
+    /// Returns `false` when the engine must stop.
+    fn simulate_tick(&mut self) -> bool {
+        if self.must_stop {
+            self.stop_reason = Some(StopReason::Stopped);
+            return false;
+        }
 
-            while poll(Duration::from_millis(0)).unwrap() {
-                if let Event::Key(key_event) = read().unwrap() {
-                    match key_event.code {
-                        KeyCode::Char('c')
-                            if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
-                        {
-                            if self.stop_on_sigint {
-                                self.killed = true;
-                                break;
+        self.step_id += 1;
+        if let Some(replay) = &self.replay
+            && self.step_id > replay.end_step()
+        {
+            // The recorded run ended here, maybe interrupted
+            self.killed |= replay.killed();
+            self.stop_reason = Some(StopReason::ReplayEnded);
+            return false;
+        }
+
+        // Time is frozen while a dialog is open
+        let paused = self.has_dialog();
+        if !paused {
+            self.tick_id += 1;
+        }
+        if self.ttl > 0 && self.tick_id >= self.ttl {
+            self.stop_reason = Some(StopReason::TimeToLive);
+            return false;
+        }
+
+        #[cfg(unix)]
+        {
+            if let Some(sig) = signal::termination_signal() {
+                self.killed = true;
+                self.stop_reason = Some(StopReason::Signal(sig));
+                return false;
+            }
+            if signal::take_suspend_request() {
+                self.suspend_requested = true;
+            }
+        }
+
+        self.poll_input();
+        if self.killed {
+            return false;
+        }
+        if paused {
+            return true;
+        }
+
+        // update the scene (all objects, movements, animations, etc.)
+        self.scene.update(self.tick_id, &mut self.objects);
+        if let Some(weather) = &mut self.weather {
+            weather.update(self.tick_id, &self.objects);
+        }
+
+        // collisions
+        for i in (0..self.collisions.len()).rev() {
+            let terminal_size = self.terminal_size;
+
+            // Retirer temporairement l'élément
+            let mut collision = self.collisions.remove(i);
+
+            if collision.is_colliding(terminal_size, &self.objects) {
+                collision.trigger(self);
+                let object = collision.object();
+                self.log.object_event(
+                    self.tick_id,
+                    "collision",
+                    object,
+                    self.objects.get(object),
+                    &[
+                        ("kind", collision.kind().to_string()),
+                        ("counter", collision.counter().to_string()),
+                    ],
+                );
+            }
+
+            // Remettre à sa place
+            self.collisions.insert(i, collision);
+        }
+
+        self.log.diff_objects(self.tick_id, &self.objects);
+        true
+    }
+
+    /// Reads the pending terminal events and dispatches them.
+    fn poll_input(&mut self) {
+        for event in self.pending_events() {
+            if let Some(encoded) = replay::encode_event(&event).filter(|_| self.log.is_enabled()) {
+                self.log.event(self.tick_id, "input", &[("value", encoded)]);
+            }
+            match event {
+                Event::Key(key_event) => {
+                    if key_event.kind == KeyEventKind::Press {
+                        match key_event.code {
+                            KeyCode::Char('c')
+                                if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+                            {
+                                if self.stop_on_sigint {
+                                    self.killed = true;
+                                    self.stop_reason = Some(StopReason::Interrupted);
+                                    break;
+                                }
+                            }
+                            // Raw mode doesn't turn Ctrl+Z into SIGTSTP
+                            #[cfg(unix)]
+                            KeyCode::Char('z')
+                                if key_event.modifiers.contains(KeyModifiers::CONTROL) =>
+                            {
+                                self.suspend_requested = true;
+                                continue;
                             }
+                            KeyCode::F(12) => {
+                                self.debug.toggle();
+                                continue;
+                            }
+                            _ => {}
                         }
-                        _ => {
-                            self.scene.handle_input(key_event.code, &mut self.objects);
+                    }
+                    if self.has_dialog() {
+                        if key_event.kind != KeyEventKind::Release {
+                            self.dispatch_dialog_key(key_event);
                         }
+                        continue;
+                    }
+                    if let Some(trigger) = self.input.key_event(key_event, self.input_clock()) {
+                        self.dispatch_key(key_event.code, key_event.modifiers, trigger);
                     }
                 }
+                Event::Mouse(mouse_event) if !self.has_dialog() => {
+                    self.dispatch_mouse(mouse_event);
+                }
+                _ => {}
             }
-            if self.killed {
-                break;
+        }
+        if self.has_dialog() {
+            return;
+        }
+        for (code, modifiers) in self.input.expire(self.input_clock()) {
+            self.log.event(
+                self.tick_id,
+                "key_timeout",
+                &[("code", format!("{:?}", code))],
+            );
+            self.dispatch_key(code, modifiers, KeyTrigger::Release);
+        }
+        for (code, modifiers) in self.input.held_keys() {
+            self.dispatch_key(code, modifiers, KeyTrigger::Held);
+        }
+    }
+
+    /// Returns the terminal events read during this step, recording them when asked.
+    ///
+    /// When replaying, the recorded events are returned instead and the keyboard
+    /// is only used to interrupt, suspend or debug the replay.
+    fn pending_events(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        while poll(Duration::from_millis(0)).unwrap() {
+            let event = read().unwrap();
+            let ctrl_key = match &event {
+                Event::Key(key) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    match key.code {
+                        KeyCode::Char(char) => Some(char),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            if self.replay.is_some() {
+                let is_system_key = matches!(ctrl_key, Some('c' | 'z'))
+                    || matches!(&event, Event::Key(key) if key.code == KeyCode::F(12));
+                if !is_system_key {
+                    continue;
+                }
+            } else if ctrl_key != Some('z') {
+                // A suspended session can't be replayed
+                replay::record(self.step_id, &event);
             }
+            events.push(event);
+        }
+        if let Some(replay) = self.replay.as_mut() {
+            events.extend(replay.take_events(self.step_id));
+        }
+        events
+    }
 
-            // update the scene (all objects, movements, animations, etc.)
-            self.scene.update(self.tick_id, &mut self.objects);
+    /// Time given to the input state, it follows the simulation steps instead of the wall clock
+    /// so held keys are released at the same step when replaying.
+    fn input_clock(&self) -> Instant {
+        self.input_epoch + self.tick_duration * self.step_id as u32
+    }
 
-            // collisions
-            for i in (0..self.collisions.len()).rev() {
-                let terminal_size = self.terminal_size;
+    /// Sends a key press to the last opened dialog.
+    fn dispatch_dialog_key(&mut self, key_event: KeyEvent) {
+        let Some(index) = self.dialogs.len().checked_sub(1) else {
+            return;
+        };
+        let mut dialog = self.dialogs.remove(index);
+        if !dialog.handle_key(key_event, self) {
+            // The callback may have opened other dialogs, they stay above
+            self.dialogs.insert(index.min(self.dialogs.len()), dialog);
+        }
+    }
 
-                // Retirer temporairement l'élément
-                let mut collision = self.collisions.remove(i);
+    /// Calls the actions of all the objects bound to the key event.
+    fn dispatch_key(&mut self, code: KeyCode, modifiers: KeyModifiers, trigger: KeyTrigger) {
+        let actions: Vec<(ObjectHandle, ObjectAction)> = self
+            .objects
+            .iter()
+            .flat_map(|(handle, object)| {
+                object
+                    .input_actions(code, modifiers, trigger)
+                    .into_iter()
+                    .map(move |action| (handle, action))
+            })
+            .collect();
+        for (handle, action) in actions {
+            action(handle, self);
+        }
+    }
 
-                if collision.is_colliding(terminal_size) {
-                    collision.trigger(self);
+    /// Dispatches a mouse event to the objects under the cursor.
+    ///
+    /// A click goes to the topmost visible object, hover changes to all of them.
+    fn dispatch_mouse(&mut self, event: MouseEvent) {
+        let point = self.scene.scene_coords(event.column, event.row);
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let clicked = self
+                    .objects
+                    .z_ordered()
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev()
+                    .find_map(|handle| {
+                        let object = &self.objects[handle];
+                        if !object.visible() || !object.contains(point) {
+                            return None;
+                        }
+                        object.click_action().map(|action| (handle, action))
+                    });
+                if let Some((handle, action)) = clicked {
+                    action(handle, self);
                 }
-
-                // Remettre à sa place
-                self.collisions.insert(i, collision);
             }
-
-            // build the ASCII frame from the scene
-            let screen = self.scene.build_screen(self.tick_id, &mut self.objects);
-
-            // Print on screen
-            let mut buffer = String::new();
-            buffer.push_str("\x1B[H"); // Move cursor to the top
-            for line in screen.iter() {
-                buffer.push_str(&line.iter().collect::<String>());
+            MouseEventKind::Moved | MouseEventKind::Drag(_) => {
+                for handle in self.objects.handles() {
+                    let object = &mut self.objects[handle];
+                    let Some(action) = object.hover_action() else {
+                        continue;
+                    };
+                    let hovered = object.visible() && object.contains(point);
+                    if hovered != object.is_hovered() {
+                        object.set_hovered(hovered);
+                        action(handle, hovered, self);
+                    }
+                }
             }
+            _ => {}
+        }
+    }
 
-            stdout.write_all(buffer.as_bytes()).unwrap();
-            stdout.flush().unwrap();
+    /// Bitmaps are drawn above the text, they are left out when overlays are shown.
+    fn graphics_enabled(&self) -> bool {
+        self.scene.graphics().is_some()
+            && self.tick_id > 0
+            && !self.has_dialog()
+            && !self.debug.is_enabled()
+    }
 
-            //  wait for the end of the tick to maintain constant tick rate
-            let elapsed = tick_start_time.elapsed();
-            if elapsed < self.tick_duration {
-                std::thread::sleep(self.tick_duration - elapsed);
-            }
+    /// Builds the ASCII frame from the scene, with the HUD and the debug overlay on top.
+    fn build_frame(&self) -> Vec<Vec<char>> {
+        let mut screen = match self.tick_id {
+            // Objects are placed on the first tick
+            0 => self.scene.blank_screen(),
+            _ => self.scene.build_screen(
+                self.tick_id,
+                &self.objects,
+                self.graphics_enabled(),
+                self.weather.as_ref(),
+            ),
+        };
+        self.hud.draw(&mut screen, self);
+        for dialog in self.dialogs.iter() {
+            draw_dialog(&mut screen, &dialog.lines());
         }
+        self.debug.draw(
+            &mut screen,
+            self.terminal_size,
+            self.tick_id,
+            &self.objects,
+            &self.collisions,
+        );
+        screen
+    }
+}
+
+/// Switches to the alternate screen in raw mode, with the cursor hidden.
+fn enter_terminal(stdout: &mut Stdout, mouse_capture: bool) {
+    stdout.execute(EnterAlternateScreen).unwrap(); // Go to alternate buffer
+    enable_raw_mode().unwrap();
+    stdout.execute(Hide).unwrap(); // Hide cursor
+    // Report key releases and repeats, ignored by terminals not supporting it
+    let _ = stdout.execute(PushKeyboardEnhancementFlags(
+        KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+            | KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+    ));
+    if mouse_capture {
+        stdout.execute(EnableMouseCapture).unwrap();
+    }
+    // Job control and termination signals are handled by the engine loop
+    #[cfg(unix)]
+    signal::install_handlers();
+}
 
-        disable_raw_mode().unwrap();
-        stdout.execute(Show).unwrap(); // Show cursor
-        stdout.execute(LeaveAlternateScreen).unwrap(); // Go back to the normal terminal
+/// Gives the terminal back in the state it was before [`enter_terminal`].
+fn leave_terminal(stdout: &mut Stdout, mouse_capture: bool) {
+    #[cfg(unix)]
+    signal::restore_default_handlers();
+    if mouse_capture {
+        stdout.execute(DisableMouseCapture).unwrap();
     }
+    let _ = stdout.execute(PopKeyboardEnhancementFlags);
+    disable_raw_mode().unwrap();
+    stdout.execute(Show).unwrap(); // Show cursor
+    stdout.execute(LeaveAlternateScreen).unwrap(); // Go back to the normal terminal
 }
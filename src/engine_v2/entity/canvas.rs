@@ -0,0 +1,198 @@
+use crate::engine_v2::entity::frame::Frame;
+use crate::engine_v2::size::Size;
+
+/// How many dots a terminal cell is split into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanvasResolution {
+    /// 2x4 dots per cell, drawn with Braille patterns
+    Braille,
+    /// 1x2 dots per cell, drawn with half blocks
+    HalfBlock,
+}
+
+impl CanvasResolution {
+    /// Returns the number of dots per cell, horizontally and vertically.
+    pub fn dots_per_cell(&self) -> (u32, u32) {
+        match self {
+            CanvasResolution::Braille => (2, 4),
+            CanvasResolution::HalfBlock => (1, 2),
+        }
+    }
+}
+
+/// A grid of dots finer than the terminal cells, to draw smooth points and lines.
+///
+/// Like the scene, dot coordinates start at the bottom left corner and `y` goes up.
+/// [`Canvas::plot`] and [`Canvas::line`] take fractional cell coordinates,
+/// so a trajectory computed in cells moves by dots instead of whole cells.
+/// A canvas is shown by an object created with
+/// [`Object::new_canvas`](crate::engine_v2::entity::object::Object::new_canvas),
+/// its empty cells let the scene behind it show through.
+///
+/// ```rust
+/// use coretilus::engine_v2::entity::canvas::Canvas;
+/// use coretilus::engine_v2::entity::canvas::CanvasResolution;
+/// use coretilus::engine_v2::size::Size;
+///
+/// let mut canvas = Canvas::new(CanvasResolution::Braille, Size::new(2, 1));
+/// assert_eq!((canvas.dot_width(), canvas.dot_height()), (4, 4));
+/// // A diagonal from the bottom left to the top right dot
+/// canvas.line((0.0, 0.0), (1.9, 0.9));
+/// assert_eq!(canvas.to_frame().content(), "⡠⠊");
+///
+/// let mut canvas = Canvas::new(CanvasResolution::HalfBlock, Size::new(3, 1));
+/// canvas.plot(0.0, 0.0);
+/// canvas.plot(1.0, 0.5);
+/// canvas.line((2.0, 0.0), (2.0, 0.9));
+/// assert_eq!(canvas.to_frame().content(), "▄▀█");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    resolution: CanvasResolution,
+    size: Size,
+    // Dots from the bottom left, row by row
+    dots: Vec<bool>,
+}
+
+impl Canvas {
+    /// Creates an empty canvas covering `size` cells.
+    pub fn new(resolution: CanvasResolution, size: Size) -> Self {
+        let (dots_x, dots_y) = resolution.dots_per_cell();
+        let dot_count = (size.width() * dots_x * size.height() * dots_y) as usize;
+        Self {
+            resolution,
+            size,
+            dots: vec![false; dot_count],
+        }
+    }
+
+    pub fn resolution(&self) -> CanvasResolution {
+        self.resolution
+    }
+
+    /// Returns the size of the canvas in cells.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn dot_width(&self) -> u32 {
+        self.size.width() * self.resolution.dots_per_cell().0
+    }
+
+    pub fn dot_height(&self) -> u32 {
+        self.size.height() * self.resolution.dots_per_cell().1
+    }
+
+    pub fn clear(&mut self) {
+        self.dots.fill(false);
+    }
+
+    /// Returns `true` when the dot is drawn, dots outside of the canvas are never drawn.
+    pub fn is_set(&self, x: i32, y: i32) -> bool {
+        self.index(x, y).is_some_and(|index| self.dots[index])
+    }
+
+    /// Draws a dot, dots outside of the canvas are ignored.
+    pub fn set_dot(&mut self, x: i32, y: i32) {
+        if let Some(index) = self.index(x, y) {
+            self.dots[index] = true;
+        }
+    }
+
+    /// Draws the dot at a position given in cells.
+    pub fn plot(&mut self, x: f64, y: f64) {
+        let (x, y) = self.to_dots(x, y);
+        self.set_dot(x, y);
+    }
+
+    /// Draws a line between two positions given in cells.
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64)) {
+        let (mut x, mut y) = self.to_dots(from.0, from.1);
+        let (end_x, end_y) = self.to_dots(to.0, to.1);
+        // Bresenham
+        let dx = (end_x - x).abs();
+        let dy = -(end_y - y).abs();
+        let step_x = if x < end_x { 1 } else { -1 };
+        let step_y = if y < end_y { 1 } else { -1 };
+        let mut error = dx + dy;
+        loop {
+            self.set_dot(x, y);
+            if x == end_x && y == end_y {
+                break;
+            }
+            let double_error = 2 * error;
+            if double_error >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if double_error <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    /// Renders the dots as a frame, cells without dots are spaces.
+    pub fn to_frame(&self) -> Frame {
+        let (dots_x, dots_y) = self.resolution.dots_per_cell();
+        let mut lines = Vec::with_capacity(self.size.height() as usize);
+        // Frames are written from the top line
+        for row in (0..self.size.height()).rev() {
+            let line: String = (0..self.size.width())
+                .map(|column| {
+                    // Dots of the cell from its top left corner
+                    let dot = |dx: u32, dy: u32| {
+                        self.is_set(
+                            (column * dots_x + dx) as i32,
+                            (row * dots_y + dots_y - 1 - dy) as i32,
+                        )
+                    };
+                    match self.resolution {
+                        CanvasResolution::Braille => braille_char(dot),
+                        CanvasResolution::HalfBlock => match (dot(0, 0), dot(0, 1)) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        },
+                    }
+                })
+                .collect();
+            lines.push(line);
+        }
+        Frame::new(lines.join("\n"))
+    }
+
+    fn to_dots(&self, x: f64, y: f64) -> (i32, i32) {
+        let (dots_x, dots_y) = self.resolution.dots_per_cell();
+        (
+            (x * dots_x as f64).floor() as i32,
+            (y * dots_y as f64).floor() as i32,
+        )
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.dot_width() as i32 || y >= self.dot_height() as i32 {
+            return None;
+        }
+        Some(y as usize * self.dot_width() as usize + x as usize)
+    }
+}
+
+/// Returns the Braille pattern of a cell, `dot(dx, dy)` tells if a dot is drawn from the top left.
+fn braille_char(dot: impl Fn(u32, u32) -> bool) -> char {
+    // Bit of each dot in the Unicode Braille patterns, by column then row
+    const BITS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+    let mut pattern = 0;
+    for (dx, column) in BITS.iter().enumerate() {
+        for (dy, bit) in column.iter().enumerate() {
+            if dot(dx as u32, dy as u32) {
+                pattern |= bit;
+            }
+        }
+    }
+    if pattern == 0 {
+        return ' ';
+    }
+    char::from_u32(0x2800 + pattern).unwrap_or(' ')
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+use crate::engine_v2::arena::ObjectArena;
+
+/// Where the ASCII art of an object comes from.
+///
+/// ```rust
+/// use coretilus::engine_v2::entity::credits::Credits;
+///
+/// let credits = Credits::new("https://github.com/mtoyoda/sl").with_author("Toyoda Masashi");
+/// assert_eq!(credits.to_string(), "Toyoda Masashi, https://github.com/mtoyoda/sl");
+/// assert_eq!(
+///     credits.to_hyperlinked_string(),
+///     "Toyoda Masashi, \x1b]8;;https://github.com/mtoyoda/sl\x1b\\https://github.com/mtoyoda/sl\x1b]8;;\x1b\\"
+/// );
+///
+/// // The artist of the piece is not always known
+/// let credits = Credits::new("https://www.asciiart.eu/animals/dogs");
+/// assert_eq!(credits.to_string(), "https://www.asciiart.eu/animals/dogs");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credits {
+    author: Option<String>,
+    source: String,
+}
+
+impl Credits {
+    /// Credits the art to the page it was taken from.
+    pub fn new(source: &str) -> Self {
+        Self {
+            author: None,
+            source: source.to_string(),
+        }
+    }
+
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        self
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Formats the credits with a clickable OSC 8 link to the source.
+    pub fn to_hyperlinked_string(&self) -> String {
+        self.format(&hyperlink(&self.source, &self.source))
+    }
+
+    fn format(&self, source: &str) -> String {
+        match &self.author {
+            Some(author) => format!("{}, {}", author, source),
+            None => source.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Credits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format(&self.source))
+    }
+}
+
+/// Wraps `text` in an OSC 8 hyperlink to `url`.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Lists the credits of the objects of a scene, one line per artwork
+/// with the names of the objects using it.
+pub fn credit_lines(objects: &ObjectArena, hyperlinks: bool) -> Vec<String> {
+    let mut credited: Vec<(&Credits, Vec<String>)> = Vec::new();
+    for (_, object) in objects.iter() {
+        let Some(credits) = object.credits() else {
+            continue;
+        };
+        let name = object.tdname();
+        match credited.iter_mut().find(|(other, _)| *other == credits) {
+            Some((_, names)) if names.contains(&name) => {}
+            Some((_, names)) => names.push(name),
+            None => credited.push((credits, vec![name])),
+        }
+    }
+    credited
+        .into_iter()
+        .map(|(credits, names)| {
+            let credits = match hyperlinks {
+                true => credits.to_hyperlinked_string(),
+                false => credits.to_string(),
+            };
+            format!("{}: {}", names.join(", "), credits)
+        })
+        .collect()
+}
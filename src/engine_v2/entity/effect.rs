@@ -0,0 +1,179 @@
+use rand::RngExt;
+
+use crate::engine_v2::random;
+
+/// Characters from the densest to the lightest, the last one is transparent.
+pub const DENSITY_RAMP: &str = "@%#*+=-:. ";
+
+/// What an [`Effect`] does to the object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectKind {
+    /// Glyphs go from the lightest characters of the ramp to their own
+    FadeIn,
+    /// Glyphs go through the lighter characters of the ramp until they vanish
+    FadeOut,
+    /// Cells appear one by one in a random order
+    DissolveIn,
+    /// Cells vanish one by one in a random order
+    DissolveOut,
+    /// The object is shown and hidden every `period` ticks
+    Blink { period: usize },
+}
+
+/// A change of the way an object is drawn over a number of ticks, see
+/// [`Object::set_effect`](crate::engine_v2::entity::object::Object::set_effect).
+///
+/// The effect starts on the next update of the object and is removed when it ends.
+/// Objects are shown when an effect making them appear is set,
+/// and hidden when an effect making them disappear ends.
+///
+/// ```rust
+/// use coretilus::engine_v2::entity::effect::Effect;
+///
+/// let mut fade = Effect::fade_out(9);
+/// assert!(!fade.advance(10));
+/// assert_eq!(fade.apply('#', 0, 0), '#');
+/// fade.advance(15);
+/// // Lighter characters of the ramp, never denser than the glyph
+/// assert_eq!(fade.apply('#', 0, 0), '=');
+/// assert_eq!(fade.apply('.', 0, 0), '.');
+/// // The last step is transparent
+/// assert!(fade.advance(19));
+/// assert_eq!(fade.apply('#', 0, 0), ' ');
+///
+/// let mut blink = Effect::blink(5, 0);
+/// blink.advance(0);
+/// blink.advance(7);
+/// assert_eq!(blink.apply('o', 0, 0), ' ');
+/// assert!(!blink.advance(10));
+/// assert_eq!(blink.apply('o', 0, 0), 'o');
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Effect {
+    kind: EffectKind,
+    // Zero means forever for blinking, right away for the others
+    duration: usize,
+    ramp: Vec<char>,
+    // Set on the first update
+    start_tick_id: Option<usize>,
+    elapsed: usize,
+    // Order in which the cells dissolve
+    seed: u64,
+}
+
+impl Effect {
+    fn new(kind: EffectKind, duration: usize) -> Self {
+        Self {
+            kind,
+            duration,
+            ramp: DENSITY_RAMP.chars().collect(),
+            start_tick_id: None,
+            elapsed: 0,
+            seed: match kind {
+                EffectKind::DissolveIn | EffectKind::DissolveOut => random::rng().random(),
+                _ => 0,
+            },
+        }
+    }
+
+    pub fn fade_in(ticks: usize) -> Self {
+        Self::new(EffectKind::FadeIn, ticks)
+    }
+
+    pub fn fade_out(ticks: usize) -> Self {
+        Self::new(EffectKind::FadeOut, ticks)
+    }
+
+    pub fn dissolve_in(ticks: usize) -> Self {
+        Self::new(EffectKind::DissolveIn, ticks)
+    }
+
+    pub fn dissolve_out(ticks: usize) -> Self {
+        Self::new(EffectKind::DissolveOut, ticks)
+    }
+
+    /// Blinks every `period` ticks during `ticks` ticks, or until the effect is removed when zero.
+    pub fn blink(period: usize, ticks: usize) -> Self {
+        Self::new(
+            EffectKind::Blink {
+                period: period.max(1),
+            },
+            ticks,
+        )
+    }
+
+    /// Replaces the density ramp of the fades, from the densest character to a space.
+    pub fn with_ramp(mut self, ramp: &str) -> Self {
+        self.ramp = ramp.chars().collect();
+        if self.ramp.last() != Some(&' ') {
+            self.ramp.push(' ');
+        }
+        self
+    }
+
+    pub fn kind(&self) -> EffectKind {
+        self.kind
+    }
+
+    /// Returns `true` when the object is hidden once the effect ended.
+    pub fn hides(&self) -> bool {
+        matches!(self.kind, EffectKind::FadeOut | EffectKind::DissolveOut)
+    }
+
+    /// Returns how far the effect went, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        match self.duration {
+            0 => 1.0,
+            duration => (self.elapsed as f64 / duration as f64).min(1.0),
+        }
+    }
+
+    /// Moves the effect to `tick_id`, returns `true` when it ended.
+    pub fn advance(&mut self, tick_id: usize) -> bool {
+        let start_tick_id = *self.start_tick_id.get_or_insert(tick_id);
+        self.elapsed = tick_id.saturating_sub(start_tick_id);
+        match self.kind {
+            EffectKind::Blink { .. } => self.duration > 0 && self.elapsed >= self.duration,
+            _ => self.elapsed >= self.duration,
+        }
+    }
+
+    /// Returns the character drawn instead of `glyph` at the cell `(x, y)` of the frame,
+    /// spaces are transparent.
+    pub fn apply(&self, glyph: char, x: usize, y: usize) -> char {
+        if glyph == ' ' {
+            return glyph;
+        }
+        match self.kind {
+            EffectKind::FadeIn => fade(glyph, 1.0 - self.progress(), &self.ramp),
+            EffectKind::FadeOut => fade(glyph, self.progress(), &self.ramp),
+            EffectKind::DissolveIn if self.threshold(x, y) < self.progress() => glyph,
+            EffectKind::DissolveOut if self.threshold(x, y) >= self.progress() => glyph,
+            EffectKind::Blink { period } if (self.elapsed / period).is_multiple_of(2) => glyph,
+            _ => ' ',
+        }
+    }
+
+    /// Returns a random value from 0 to 1 for the cell, always the same for a given effect.
+    fn threshold(&self, x: usize, y: usize) -> f64 {
+        // SplitMix64 of the seed and the cell
+        let mut value = self.seed ^ ((x as u64) << 32 | y as u64);
+        value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^= value >> 31;
+        (value >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Draws `glyph` lighter by `amount` through `ramp`, from 0 (unchanged) to 1 (transparent).
+pub(crate) fn fade(glyph: char, amount: f64, ramp: &[char]) -> char {
+    let last = ramp.len() - 1;
+    let level = (amount * last as f64).round() as usize;
+    // Characters missing from the ramp are the densest
+    let density = ramp.iter().position(|&char| char == glyph).unwrap_or(0);
+    match level {
+        0 => glyph,
+        _ => ramp[level.max(density).min(last)],
+    }
+}
@@ -1,5 +1,8 @@
 use std::borrow::Cow;
 
+use crate::engine_v2::capabilities::Capabilities;
+use crate::engine_v2::capabilities::ascii_fallback;
+
 /// A single frame of an animation, containing ASCII art and the number of ticks to display it.
 ///
 /// The `ascii` field holds the ASCII art string, and `ticks` determines how many animation
@@ -15,10 +18,27 @@ use std::borrow::Cow;
 ///
 /// let frame2 = Frame::new_ticks("Hello, world!", 200);
 /// assert_eq!(frame2.ticks(), 200);
+///
+/// let frame3 = Frame::new(" o\n/|\\");
+/// assert_eq!(frame3.get_width(), 3);
+/// assert_eq!(frame3.get_height(), 2);
+/// assert_eq!(frame3.glyphs()[1], vec!['/', '|', '\\']);
+///
+/// // Terminals without Unicode get an ASCII variant, chosen or converted automatically
+/// let frame4 = Frame::new("┌─┐\n└─┘");
+/// assert_eq!(frame4.glyphs_for(false)[0], vec!['+', '-', '+']);
+/// let frame5 = Frame::new("▶").with_ascii_variant("=>");
+/// assert_eq!(frame5.glyphs_for(false)[0], vec!['=', '>']);
+/// assert_eq!(frame5.glyphs_for(true)[0], vec!['▶']);
 /// ```
 #[derive(Debug, Clone)]
 pub struct Frame {
     ascii: Cow<'static, str>,
+    // The ASCII art split in lines of chars, computed once
+    glyphs: Vec<Vec<char>>,
+    width: u32,
+    // Glyphs and width used when the terminal can't show Unicode, None for ASCII only frames
+    ascii_variant: Option<(Vec<Vec<char>>, u32)>,
     // Number of ticks to keep this frame showed
     // Zero means use default from Animation
     ticks: usize,
@@ -30,10 +50,7 @@ impl Frame {
     where
         S: Into<Cow<'static, str>>,
     {
-        Self {
-            ascii: ascii.into(),
-            ticks: 0,
-        }
+        Self::new_ticks(ascii, 0)
     }
 
     /// Creates a new `Frame` with the specified ASCII art and the number of ticks that this frame should be displayed for.
@@ -41,12 +58,28 @@ impl Frame {
     where
         S: Into<Cow<'static, str>>,
     {
+        let ascii = ascii.into();
+        let (glyphs, width) = split_glyphs(&ascii);
+        let ascii_variant = (!ascii.is_ascii()).then(|| {
+            let converted = ascii.chars().map(ascii_fallback).collect::<String>();
+            split_glyphs(&converted)
+        });
         Self {
-            ascii: ascii.into(),
+            ascii,
+            glyphs,
+            width,
+            ascii_variant,
             ticks,
         }
     }
 
+    /// Replaces the frame drawn when the terminal can't show Unicode,
+    /// by default the Unicode characters are converted to similar ASCII ones.
+    pub fn with_ascii_variant(mut self, variant: &str) -> Self {
+        self.ascii_variant = Some(split_glyphs(variant));
+        self
+    }
+
     /// Returns a reference to the ASCII art content of the frame.
     pub fn content(&self) -> &str {
         &self.ascii
@@ -62,17 +95,38 @@ impl Frame {
         self.ascii.lines().map(|line| line.to_string()).collect()
     }
 
+    /// Returns the ASCII art as lines of chars, from top to bottom,
+    /// in the variant the terminal is able to show.
+    pub fn glyphs(&self) -> &[Vec<char>] {
+        self.glyphs_for(Capabilities::global().unicode())
+    }
+
+    /// Returns the lines of chars for a terminal showing Unicode or not.
+    pub fn glyphs_for(&self, unicode: bool) -> &[Vec<char>] {
+        self.variant(unicode).0
+    }
+
     /// Returns the number of lines (height) in the frame's ASCII art.
     pub fn get_height(&self) -> u32 {
-        self.get_lines().len() as u32
+        self.glyphs().len() as u32
     }
 
     /// Returns the width (in characters) in the frame's ASCII art.
     pub fn get_width(&self) -> u32 {
-        self.ascii
-            .lines()
-            .map(|line| line.chars().count())
-            .max()
-            .unwrap_or(0) as u32
+        self.variant(Capabilities::global().unicode()).1
+    }
+
+    fn variant(&self, unicode: bool) -> (&[Vec<char>], u32) {
+        match &self.ascii_variant {
+            Some((glyphs, width)) if !unicode => (glyphs, *width),
+            _ => (&self.glyphs, self.width),
+        }
     }
 }
+
+/// Splits an ASCII art in lines of chars and returns them with the widest line length.
+fn split_glyphs(ascii: &str) -> (Vec<Vec<char>>, u32) {
+    let glyphs: Vec<Vec<char>> = ascii.lines().map(|line| line.chars().collect()).collect();
+    let width = glyphs.iter().map(|line| line.len()).max().unwrap_or(0) as u32;
+    (glyphs, width)
+}
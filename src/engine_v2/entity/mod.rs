@@ -1,5 +1,11 @@
+pub mod canvas;
+pub mod credits;
+pub mod effect;
 pub mod frame;
 pub mod movement;
 pub mod object;
+pub mod parallax;
 pub mod sprite;
 pub mod sprite_animation;
+pub mod tile_map;
+pub mod trail;
@@ -1,5 +1,6 @@
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
 use crate::engine_v2::coords::Coords;
-use crate::engine_v2::entity::object::ObjectRef;
 use crate::engine_v2::position::Position;
 use crate::engine_v2::position::XTermPosition;
 use crate::engine_v2::position::YTermPosition;
@@ -12,6 +13,7 @@ pub enum Direction {
     Circular,
     Relative,
     Manual,
+    Path,
     None,
 }
 
@@ -31,10 +33,12 @@ pub struct Movement {
     is_done: bool,
     offset: Coords,
     ttl: usize, // Number of tick to live, 0 means infinite
-    parent_object: Option<ObjectRef>,
+    parent_object: Option<ObjectHandle>,
     started_tick_id: Option<usize>,
     radius: usize,
     active: bool,
+    // Positions followed by a path movement
+    waypoints: Vec<Coords>,
 }
 
 impl Movement {
@@ -61,6 +65,7 @@ impl Movement {
             started_tick_id: None,
             radius: 0,
             active: true,
+            waypoints: vec![],
         }
     }
 
@@ -79,6 +84,7 @@ impl Movement {
             started_tick_id: None,
             radius: 0,
             active: true,
+            waypoints: vec![],
         }
     }
 
@@ -97,6 +103,7 @@ impl Movement {
             started_tick_id: None,
             radius,
             active: true,
+            waypoints: vec![],
         }
     }
 
@@ -115,10 +122,11 @@ impl Movement {
             started_tick_id: None,
             radius: 0,
             active: true,
+            waypoints: vec![],
         }
     }
 
-    pub fn new_relative(object: ObjectRef, offset: Coords) -> Self {
+    pub fn new_relative(object: ObjectHandle, offset: Coords) -> Self {
         Self {
             current_coordinate_id: 0,
             path: vec![],
@@ -133,6 +141,38 @@ impl Movement {
             started_tick_id: None,
             radius: 0,
             active: true,
+            waypoints: vec![],
+        }
+    }
+
+    /// Moves through `waypoints` one after the other, like the paths found in a
+    /// [`TileMap`](crate::engine_v2::entity::tile_map::TileMap).
+    pub fn new_path(waypoints: Vec<Coords>, speed: i32) -> Self {
+        let start = waypoints.first().copied().unwrap_or(Coords::new(0, 0, 0));
+        let end = waypoints.last().copied().unwrap_or(start);
+        Self {
+            current_coordinate_id: 0,
+            path: vec![],
+            start: Position::new(
+                XTermPosition::Coord(start.x()),
+                YTermPosition::Coord(start.y()),
+                start.z(),
+            ),
+            end: Position::new(
+                XTermPosition::Coord(end.x()),
+                YTermPosition::Coord(end.y()),
+                end.z(),
+            ),
+            direction: Direction::Path,
+            speed,
+            is_done: false,
+            offset: Coords::new(0, 0, 0),
+            ttl: 0,
+            parent_object: None,
+            started_tick_id: None,
+            radius: 0,
+            active: true,
+            waypoints,
         }
     }
 
@@ -168,26 +208,30 @@ impl Movement {
         self.is_done
     }
 
-    pub fn get_coordinate(&self, _tick_id: usize) -> Coords {
+    pub fn parent_object(&self) -> Option<ObjectHandle> {
+        self.parent_object
+    }
+
+    /// Returns the coordinate of the movement at the current step,
+    /// the parent of a relative movement is looked up in `objects`.
+    /// It is `None` for a relative movement whose parent was removed.
+    pub fn get_coordinate(&self, _tick_id: usize, objects: &ObjectArena) -> Option<Coords> {
         match self.direction {
             Direction::Relative => {
-                let parent_coords = self
-                    .parent_object
-                    .as_ref()
-                    .unwrap()
-                    .borrow_mut()
+                let parent_coords = objects
+                    .get(self.parent_object?)?
                     .movement()
-                    .get_coordinate(_tick_id);
-                parent_coords + self.offset
+                    .get_coordinate(_tick_id, objects)?;
+                Some(parent_coords + self.offset)
             }
             _ => {
                 if self.path.is_empty() {
                     panic!("Object has no movement defined");
                 }
                 if self.current_coordinate_id >= self.path.len() {
-                    return self.path[self.path.len() - 1];
+                    return Some(self.path[self.path.len() - 1]);
                 }
-                self.path[self.current_coordinate_id] + self.offset
+                Some(self.path[self.current_coordinate_id] + self.offset)
             }
         }
     }
@@ -218,6 +262,7 @@ impl Movement {
                 }
                 path
             }
+            Direction::Path => self.add_speed(self.waypoints.clone()),
             Direction::Circular => {
                 let path = arc_path(
                     self.start.resolve(terminal_size, sprite_size),
@@ -256,7 +301,13 @@ impl Movement {
         self.offset
     }
 
-    pub fn advance(&mut self, tick_id: usize, terminal_size: Size, sprite_size: Size) {
+    pub fn advance(
+        &mut self,
+        tick_id: usize,
+        terminal_size: Size,
+        sprite_size: Size,
+        objects: &ObjectArena,
+    ) {
         if !self.has_started() {
             self.started_tick_id = Some(tick_id);
         }
@@ -264,9 +315,10 @@ impl Movement {
         if self.ttl > 0 && tick_id >= self.ttl {
             self.is_done = true;
         }
-        if self.direction() == Direction::Relative {
+        if self.direction() == Direction::Relative
+            && let Some(coords) = self.get_coordinate(tick_id, objects)
+        {
             // Handle out right and top
-            let coords = self.get_coordinate(tick_id);
             if coords.x() >= terminal_size.width() as i32
                 || coords.y() >= terminal_size.height() as i32
             {
@@ -284,7 +336,7 @@ impl Movement {
             let start_tick = self.started_tick_id.unwrap();
             self.is_done = tick_id - start_tick >= self.path.len();
         }
-        if self.direction() == Direction::Circular {
+        if self.direction() == Direction::Circular || self.direction() == Direction::Path {
             let start_tick = self.started_tick_id.unwrap();
             self.is_done = tick_id - start_tick >= self.path.len();
         }
@@ -1,20 +1,36 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::panic;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crossterm::event::KeyCode;
+use crossterm::event::KeyModifiers;
 use uuid::Uuid;
 
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
 use crate::engine_v2::collision::Collider;
 use crate::engine_v2::coords::Coords;
+use crate::engine_v2::engine::Engine;
+use crate::engine_v2::entity::canvas::Canvas;
+use crate::engine_v2::entity::credits::Credits;
+use crate::engine_v2::entity::effect::Effect;
 use crate::engine_v2::entity::frame::Frame;
 use crate::engine_v2::entity::movement::Movement;
+use crate::engine_v2::entity::parallax::ParallaxLayer;
 use crate::engine_v2::entity::sprite::Sprite;
+use crate::engine_v2::entity::sprite_animation::FrameContext;
+use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
+use crate::engine_v2::entity::tile_map::TileMap;
+use crate::engine_v2::entity::trail::Trail;
+use crate::engine_v2::graphics::Bitmap;
+use crate::engine_v2::input::KeyBinding;
+use crate::engine_v2::input::KeyTrigger;
 use crate::engine_v2::size::Size;
 
-pub type ObjectRef = Rc<RefCell<Object>>;
-type ObjectAction = Box<dyn Fn(ObjectRef)>;
+/// Called with the handle of the object the action is bound to.
+pub type ObjectAction = Arc<dyn Fn(ObjectHandle, &mut Engine) + Send + Sync>;
+/// Called with `true` when the mouse enters the object, `false` when it leaves.
+pub type ObjectHoverAction = Arc<dyn Fn(ObjectHandle, bool, &mut Engine) + Send + Sync>;
 
 pub struct Object {
     id: Uuid,
@@ -26,12 +42,34 @@ pub struct Object {
     sprites: Vec<Sprite>,
     active_sprite: usize,
     coords: Coords,
-    coords_history: Vec<Coords>,
+    // Previous positions, the last one being the position of the previous tick.
+    // Only changes are kept, as many as the trail needs
+    coords_history: VecDeque<Coords>,
     visible: bool,
-    // map key -> action
-    pub input_actions: HashMap<KeyCode, ObjectAction>,
+    // ghosts left at the previous positions
+    trail: Option<Trail>,
+    // changes the way the object is drawn for a while
+    effect: Option<Effect>,
+    // key binding -> action
+    input_actions: Vec<(KeyBinding, ObjectAction)>,
+    // mouse actions
+    click_action: Option<ObjectAction>,
+    hover_action: Option<ObjectHoverAction>,
+    hovered: bool,
     // collider
     collider: Collider,
+    // the collider follows the size of the frame, it was not given
+    fitted_collider: bool,
+    // drawn instead of the sprites when set
+    canvas: Option<Canvas>,
+    // drawn instead of the frames on terminals supporting graphics
+    bitmap: Option<Arc<Bitmap>>,
+    // where the art comes from
+    credits: Option<Credits>,
+    // tiles drawn by the object, with their properties
+    tile_map: Option<TileMap>,
+    // background redrawn when it scrolls
+    parallax: Option<ParallaxLayer>,
 }
 
 impl Object {
@@ -40,13 +78,14 @@ impl Object {
         tdname: String,
         sprites: Vec<Sprite>,
         collider: Option<Collider>,
-    ) -> ObjectRef {
+    ) -> Self {
         /*let trajectory = Trajectory::new_none(
             Position::new(XTermPosition::Coord(0), YTermPosition::Coord(0)),
             Position::new(XTermPosition::Coord(0), YTermPosition::Coord(0)),
             Direction::None,
         );*/
         let id = Uuid::new_v4();
+        let fitted_collider = collider.is_none();
         let col: Collider = match collider {
             Some(c) => c,
             None => {
@@ -54,19 +93,56 @@ impl Object {
                 Collider::new(Coords::new(0, 0, 0), size, true)
             }
         };
-        Rc::new(RefCell::new(Self {
+        Self {
             id,
             tdid,
             tdname,
             coords: Coords::new(0, 0, 0),
-            coords_history: vec![],
+            coords_history: VecDeque::new(),
             movement: Movement::new_none(),
             sprites,
             active_sprite: 0,
             visible: true,
-            input_actions: HashMap::new(),
+            trail: None,
+            effect: None,
+            input_actions: Vec::new(),
+            click_action: None,
+            hover_action: None,
+            hovered: false,
             collider: col,
-        }))
+            fitted_collider,
+            canvas: None,
+            bitmap: None,
+            credits: None,
+            tile_map: None,
+            parallax: None,
+        }
+    }
+
+    /// Creates an object showing a sub-cell canvas, see [`Canvas`].
+    pub fn new_canvas(tdid: u64, tdname: String, canvas: Canvas) -> Self {
+        let sprite = Sprite::new(SpriteAnimation::new_static(canvas.to_frame()), true);
+        let mut object = Self::new(tdid, tdname, vec![sprite], None);
+        object.canvas = Some(canvas);
+        object
+    }
+
+    /// Creates an object drawing a tile map as a single frame, see [`TileMap`].
+    pub fn new_tile_map(tdid: u64, tdname: String, tile_map: TileMap) -> Self {
+        let sprite = Sprite::new(SpriteAnimation::new_static(tile_map.to_frame()), true);
+        let mut object = Self::new(tdid, tdname, vec![sprite], None);
+        object.tile_map = Some(tile_map);
+        object
+    }
+
+    /// Creates an object drawing a scrolling background layer, see [`ParallaxLayer`].
+    /// Backgrounds don't collide.
+    pub fn new_parallax(tdid: u64, tdname: String, layer: ParallaxLayer) -> Self {
+        let sprite = Sprite::new(SpriteAnimation::new_static(Frame::new("")), true);
+        let collider = Collider::new(Coords::new(0, 0, 0), Size::new(0, 0), false);
+        let mut object = Self::new(tdid, tdname, vec![sprite], Some(collider));
+        object.parallax = Some(layer);
+        object
     }
     // Id
     pub fn id(&self) -> Uuid {
@@ -79,6 +155,14 @@ impl Object {
     pub fn tdname(&self) -> String {
         self.tdname.clone()
     }
+    // Credits
+    pub fn credits(&self) -> Option<&Credits> {
+        self.credits.as_ref()
+    }
+
+    pub fn set_credits(&mut self, credits: Option<Credits>) {
+        self.credits = credits;
+    }
     // Coords
     pub fn coords(&self) -> Coords {
         self.coords
@@ -92,6 +176,18 @@ impl Object {
         &self.sprites[self.active_sprite]
     }
 
+    /// Returns the index of the sprite drawn among the sprites of the object.
+    pub fn active_sprite(&self) -> usize {
+        self.active_sprite
+    }
+
+    /// Draws the sprite at `index` from now on, indexes out of the sprites are ignored.
+    pub fn set_active_sprite(&mut self, index: usize) {
+        if index < self.sprites.len() {
+            self.active_sprite = index;
+        }
+    }
+
     pub fn activate_sprite(&mut self, tick_id: usize) {
         self.sprites[self.active_sprite].set_activate(true);
         self.sprites[self.active_sprite].reset(tick_id);
@@ -101,11 +197,52 @@ impl Object {
         self.sprites[self.active_sprite].set_activate(false);
     }
 
+    pub fn is_sprite_active(&self) -> bool {
+        self.sprites[self.active_sprite].is_active()
+    }
+
+    // Tile map
+    pub fn tile_map(&self) -> Option<&TileMap> {
+        self.tile_map.as_ref()
+    }
+
+    // Canvas
+    pub fn canvas(&self) -> Option<&Canvas> {
+        self.canvas.as_ref()
+    }
+
+    /// Draws on the canvas of the object with `draw`, the object shows it right away.
+    /// Does nothing when the object was not created with [`Object::new_canvas`].
+    pub fn draw_canvas(&mut self, draw: impl FnOnce(&mut Canvas)) {
+        let Some(canvas) = self.canvas.as_mut() else {
+            return;
+        };
+        draw(canvas);
+        let frame = canvas.to_frame();
+        self.sprites[self.active_sprite] = Sprite::new(SpriteAnimation::new_static(frame), true);
+        self.fit_collider();
+    }
+
+    // Bitmap
+    /// Draws `bitmap` over the cells of the current frame on terminals supporting graphics,
+    /// the frame is still drawn on the others.
+    pub fn set_bitmap(&mut self, bitmap: Option<Bitmap>) {
+        self.bitmap = bitmap.map(Arc::new);
+    }
+
+    pub fn bitmap(&self) -> Option<&Arc<Bitmap>> {
+        self.bitmap.as_ref()
+    }
+
     // Frame
     pub fn set_frame_id(&mut self, frame_id: usize) {
         self.sprites[self.active_sprite].set_frame_id(frame_id);
     }
 
+    pub fn frame_index(&self) -> usize {
+        self.sprites[self.active_sprite].frame_index()
+    }
+
     pub fn current_frame(&self) -> &Frame {
         self.sprites[self.active_sprite].current_frame()
     }
@@ -115,20 +252,30 @@ impl Object {
         Size::new(frame.get_width(), frame.get_height())
     }
 
-    pub fn update(&mut self, tick_id: usize, terminal_size: Size) {
+    /// Moves and animates the object, `objects` is used to resolve relative movements.
+    pub fn update(&mut self, tick_id: usize, terminal_size: Size, objects: &ObjectArena) {
         let sprite = &mut self.sprites[self.active_sprite];
         if self.movement.is_active() {
-            self.movement.advance(tick_id, terminal_size, sprite.size());
+            self.movement
+                .advance(tick_id, terminal_size, sprite.size(), objects);
+        }
+        if self.coords_history.back() != Some(&self.coords) {
+            let capacity = self.trail.as_ref().map_or(0, Trail::length) + 1;
+            while self.coords_history.len() >= capacity {
+                self.coords_history.pop_front();
+            }
+            self.coords_history.push_back(self.coords);
         }
-        self.coords_history.push(self.coords);
 
         let tdid = self.tdid; // Capturer avant le catch_unwind
         let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-            self.movement.get_coordinate(tick_id)
+            self.movement.get_coordinate(tick_id, objects)
         }));
 
         self.coords = match result {
-            Ok(coords) => coords,
+            Ok(Some(coords)) => coords,
+            // The parent of the object is gone, it stays where it was
+            Ok(None) => self.coords,
             Err(_) => {
                 panic!("Can not find coords for tdid: {}", tdid);
             }
@@ -136,9 +283,9 @@ impl Object {
 
         let mut moved_x = true;
         let mut moved_y = true;
-        if !self.coords_history.is_empty() {
-            moved_x = self.coords.x() != self.coords_history[self.coords_history.len() - 1].x();
-            moved_y = self.coords.y() != self.coords_history[self.coords_history.len() - 1].y();
+        if let Some(previous) = self.coords_history.back() {
+            moved_x = self.coords.x() != previous.x();
+            moved_y = self.coords.y() != previous.y();
         }
         if sprite.is_active() {
             sprite.advance(
@@ -148,6 +295,26 @@ impl Object {
                 self.movement.is_active(),
                 self.movement.is_done(),
             );
+            let generated = sprite.generate(&FrameContext {
+                tick_id,
+                coords: self.coords,
+                is_moving: self.movement.is_active() && !self.movement.is_done(),
+            });
+            if generated {
+                self.fit_collider();
+            }
+        }
+        if let Some(effect) = self.effect.as_mut()
+            && effect.advance(tick_id)
+        {
+            self.visible &= !effect.hides();
+            self.effect = None;
+        }
+        if let Some(layer) = self.parallax.as_mut()
+            && let Some(frame) = layer.refresh(tick_id, terminal_size, objects)
+        {
+            self.sprites[self.active_sprite] =
+                Sprite::new(SpriteAnimation::new_static(frame), true);
         }
     }
 
@@ -165,7 +332,11 @@ impl Object {
         self.movement = movement;
     }
 
-    pub fn movement(&mut self) -> &mut Movement {
+    pub fn movement(&self) -> &Movement {
+        &self.movement
+    }
+
+    pub fn movement_mut(&mut self) -> &mut Movement {
         &mut self.movement
     }
 
@@ -191,6 +362,23 @@ impl Object {
         &self.collider
     }
 
+    /// Resizes the collider built from the first frame when the frame is drawn again,
+    /// the collisions then follow the frame generated for the tick in its variant.
+    fn fit_collider(&mut self) {
+        let size = self.sprites[self.active_sprite].size();
+        let collider_size = self.collider.size();
+        if self.fitted_collider
+            && (size.width(), size.height()) != (collider_size.width(), collider_size.height())
+        {
+            self.collider = Collider::new(Coords::new(0, 0, 0), size, self.collider.is_active());
+        }
+    }
+
+    /// Returns `true` when `point` (in scene coordinates) is inside the object's collider.
+    pub fn contains(&self, point: Coords) -> bool {
+        !self.collider.is_null() && self.collider.contains(self.coords, point)
+    }
+
     // Visible
     pub fn visible(&self) -> bool {
         self.visible
@@ -200,12 +388,182 @@ impl Object {
         self.visible = value;
     }
 
+    // Trail
+    /// Leaves ghosts of the object at its previous positions.
+    pub fn set_trail(&mut self, trail: Option<Trail>) {
+        self.trail = trail;
+    }
+
+    pub fn trail(&self) -> Option<&Trail> {
+        self.trail.as_ref()
+    }
+
+    /// Returns the previous positions the trail is drawn at, from the newest.
+    pub fn trail_positions(&self) -> Vec<Coords> {
+        match &self.trail {
+            Some(trail) => trail
+                .positions(self.coords, self.coords_history.iter())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Effect
+    /// Fades, dissolves or blinks the object, replacing the current effect.
+    /// The object is shown right away when the effect makes it appear.
+    pub fn set_effect(&mut self, effect: Option<Effect>) {
+        if effect.as_ref().is_some_and(|effect| !effect.hides()) {
+            self.visible = true;
+        }
+        self.effect = effect;
+    }
+
+    pub fn effect(&self) -> Option<&Effect> {
+        self.effect.as_ref()
+    }
+
     // Key event
-    // Used to add action based on an event
-    pub fn on_key(object: &ObjectRef, key: KeyCode, action: impl Fn(ObjectRef) + 'static) {
+    // Used to add action when a key is pressed
+    pub fn on_key(
+        &mut self,
+        key: KeyCode,
+        action: impl Fn(ObjectHandle, &mut Engine) + Send + Sync + 'static,
+    ) {
+        self.on_key_binding(KeyBinding::press(key), action);
+    }
+
+    // Used to add action on a key press, repeat, release or hold with modifiers
+    pub fn on_key_binding(
+        &mut self,
+        binding: KeyBinding,
+        action: impl Fn(ObjectHandle, &mut Engine) + Send + Sync + 'static,
+    ) {
+        self.input_actions.push((binding, Arc::new(action)));
+    }
+
+    pub fn input_actions(
+        &self,
+        key: KeyCode,
+        modifiers: KeyModifiers,
+        trigger: KeyTrigger,
+    ) -> Vec<ObjectAction> {
+        self.input_actions
+            .iter()
+            .filter(|(binding, _)| binding.matches(key, modifiers, trigger))
+            .map(|(_, action)| action.clone())
+            .collect()
+    }
+
+    // Mouse event
+    // Used to add action when the object is clicked, needs the engine mouse capture
+    pub fn on_click(&mut self, action: impl Fn(ObjectHandle, &mut Engine) + Send + Sync + 'static) {
+        self.click_action = Some(Arc::new(action));
+    }
+
+    pub fn click_action(&self) -> Option<ObjectAction> {
+        self.click_action.clone()
+    }
+
+    // Used to add action when the mouse enters or leaves the object
+    pub fn on_hover(
+        &mut self,
+        action: impl Fn(ObjectHandle, bool, &mut Engine) + Send + Sync + 'static,
+    ) {
+        self.hover_action = Some(Arc::new(action));
+    }
+
+    pub fn hover_action(&self) -> Option<ObjectHoverAction> {
+        self.hover_action.clone()
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    pub fn set_hovered(&mut self, value: bool) {
+        self.hovered = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_v2::position::Position;
+    use crate::engine_v2::position::XTermPosition;
+    use crate::engine_v2::position::YTermPosition;
+
+    /// A bar growing by one cell each tick, standing at the bottom left corner.
+    fn bar(collider: Option<Collider>) -> Object {
+        let animation = SpriteAnimation::new_procedural(|context: &FrameContext| {
+            Frame::new("=".repeat(context.tick_id + 1))
+        });
+        let mut object = Object::new(
+            1,
+            String::from("bar"),
+            vec![Sprite::new(animation, true)],
+            collider,
+        );
+        let start = Position::new(XTermPosition::Coord(0), YTermPosition::Coord(0), 0);
+        object.set_movement(Movement::new_stationary(start, 0));
+        object.compute_predefined_path(Size::new(20, 5));
         object
-            .borrow_mut()
-            .input_actions
-            .insert(key, Box::new(action));
+    }
+
+    #[test]
+    fn test_relative_to_removed_parent() {
+        let mut objects = ObjectArena::new();
+        let parent = bar(None);
+        let parent = objects.insert(parent);
+        let sprite = Sprite::new(SpriteAnimation::new_static(Frame::new("o")), true);
+        let mut child = Object::new(2, String::from("child"), vec![sprite], None);
+        child.set_movement(Movement::new_relative(parent, Coords::new(1, 2, 0)));
+
+        child.update(1, Size::new(20, 5), &objects);
+        assert_eq!(child.coords(), Coords::new(1, 2, 0));
+        // Without its parent the object keeps its last position
+        objects.remove(parent);
+        child.update(2, Size::new(20, 5), &objects);
+        assert_eq!(child.coords(), Coords::new(1, 2, 0));
+    }
+
+    #[test]
+    fn test_collider_follows_generated_frame() {
+        let mut object = bar(None);
+        assert_eq!(object.collider().size().width(), 1);
+
+        object.update(2, Size::new(20, 5), &ObjectArena::new());
+        assert_eq!(object.current_frame().content(), "===");
+        let size = object.collider().size();
+        assert_eq!((size.width(), size.height()), (3, 1));
+        assert!(object.contains(Coords::new(2, 0, 0)));
+        assert!(!object.contains(Coords::new(3, 0, 0)));
+
+        // The collider is measured on the variant the terminal shows
+        let animation = SpriteAnimation::new_procedural(|_: &FrameContext| {
+            Frame::new("▶").with_ascii_variant("=>")
+        });
+        let mut object = Object::new(
+            2,
+            String::from("arrow"),
+            vec![Sprite::new(animation, true)],
+            None,
+        );
+        object.set_movement(Movement::new_stationary(
+            Position::new(XTermPosition::Coord(0), YTermPosition::Coord(0), 0),
+            0,
+        ));
+        object.compute_predefined_path(Size::new(20, 5));
+        object.update(1, Size::new(20, 5), &ObjectArena::new());
+        assert_eq!(
+            object.collider().size().width(),
+            object.current_frame().get_width()
+        );
+
+        // A given collider is kept
+        let collider = Collider::new(Coords::new(0, 0, 0), Size::new(1, 1), true);
+        let mut object = bar(Some(collider));
+        object.update(2, Size::new(20, 5), &ObjectArena::new());
+        assert_eq!(object.current_frame().content(), "===");
+        assert_eq!(object.collider().size().width(), 1);
     }
 }
@@ -0,0 +1,150 @@
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
+use crate::engine_v2::entity::frame::Frame;
+use crate::engine_v2::size::Size;
+
+/// The axis a layer is repeated and scrolls along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollAxis {
+    Horizontal,
+    Vertical,
+}
+
+/// How fast a layer scrolls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scroll {
+    /// The layer doesn't move
+    None,
+    /// Moves by one cell every `n` ticks toward the left or the bottom,
+    /// the other way when `n` is negative
+    Ticks(i32),
+    /// Moves by one cell each time the object moves by `n` cells, the other way,
+    /// as if the camera was following the object. A negative `n` moves along
+    Follow(ObjectHandle, i32),
+}
+
+/// A background repeating a frame along an axis across the whole screen.
+///
+/// The layer of an object created with
+/// [`Object::new_parallax`](crate::engine_v2::entity::object::Object::new_parallax)
+/// is redrawn when it scrolls. Layers at different z-coordinates scrolling at
+/// different speeds give depth, the farthest ones being the slowest.
+/// Spaces of the frame are transparent.
+///
+/// ```rust
+/// use coretilus::engine_v2::arena::ObjectArena;
+/// use coretilus::engine_v2::entity::frame::Frame;
+/// use coretilus::engine_v2::entity::parallax::ParallaxLayer;
+/// use coretilus::engine_v2::entity::parallax::Scroll;
+/// use coretilus::engine_v2::entity::parallax::ScrollAxis;
+/// use coretilus::engine_v2::size::Size;
+///
+/// let layer = ParallaxLayer::new(Frame::new("/\\_"), ScrollAxis::Horizontal, Scroll::Ticks(2));
+/// let terminal_size = Size::new(7, 4);
+/// assert_eq!(layer.render(0, terminal_size).content(), "/\\_/\\_/");
+/// // One cell to the left every 2 ticks
+/// let offset = layer.offset(2, &ObjectArena::new());
+/// assert_eq!(layer.render(offset, terminal_size).content(), "\\_/\\_/\\");
+///
+/// let layer = ParallaxLayer::new(Frame::new(".\n*"), ScrollAxis::Vertical, Scroll::None);
+/// assert_eq!(layer.render(1, terminal_size).content(), "*\n.\n*\n.");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParallaxLayer {
+    pattern: Frame,
+    axis: ScrollAxis,
+    scroll: Scroll,
+    // Offset and terminal size of the frame drawn last
+    drawn: Option<(i32, u32, u32)>,
+}
+
+impl ParallaxLayer {
+    pub fn new(pattern: Frame, axis: ScrollAxis, scroll: Scroll) -> Self {
+        Self {
+            pattern,
+            axis,
+            scroll,
+            drawn: None,
+        }
+    }
+
+    pub fn axis(&self) -> ScrollAxis {
+        self.axis
+    }
+
+    pub fn scroll(&self) -> Scroll {
+        self.scroll
+    }
+
+    /// Returns how many cells the layer scrolled at `tick_id`.
+    pub fn offset(&self, tick_id: usize, objects: &ObjectArena) -> i32 {
+        match self.scroll {
+            Scroll::None | Scroll::Ticks(0) | Scroll::Follow(_, 0) => 0,
+            Scroll::Ticks(ticks) => tick_id as i32 / ticks,
+            Scroll::Follow(object, cells) => {
+                let Some(object) = objects.get(object) else {
+                    return 0;
+                };
+                let coords = object.coords();
+                let position = match self.axis {
+                    ScrollAxis::Horizontal => coords.x(),
+                    ScrollAxis::Vertical => coords.y(),
+                };
+                position.div_euclid(cells)
+            }
+        }
+    }
+
+    /// Draws the pattern repeated across the screen, scrolled by `offset` cells.
+    pub fn render(&self, offset: i32, terminal_size: Size) -> Frame {
+        let glyphs = self.pattern.glyphs();
+        let pattern_width = self.pattern.get_width() as i32;
+        let pattern_height = glyphs.len() as i32;
+        if pattern_width == 0 || pattern_height == 0 {
+            return Frame::new("");
+        }
+        let glyph = |x: i32, y: i32| {
+            glyphs[y.rem_euclid(pattern_height) as usize]
+                .get(x.rem_euclid(pattern_width) as usize)
+                .copied()
+                .unwrap_or(' ')
+        };
+        let lines: Vec<String> = match self.axis {
+            ScrollAxis::Horizontal => (0..pattern_height)
+                .map(|y| {
+                    (0..terminal_size.width() as i32)
+                        .map(|x| glyph(x + offset, y))
+                        .collect()
+                })
+                .collect(),
+            ScrollAxis::Vertical => {
+                // Lines are written from the top, the offset scrolls toward the bottom
+                let height = terminal_size.height() as i32;
+                (0..height)
+                    .map(|row| {
+                        let y = pattern_height - 1 - (height - 1 - row + offset);
+                        (0..pattern_width).map(|x| glyph(x, y)).collect()
+                    })
+                    .collect()
+            }
+        };
+        Frame::new(lines.join("\n"))
+    }
+
+    /// Returns the frame to draw when the layer scrolled or the terminal was resized,
+    /// `None` when the frame drawn last is still right.
+    pub(crate) fn refresh(
+        &mut self,
+        tick_id: usize,
+        terminal_size: Size,
+        objects: &ObjectArena,
+    ) -> Option<Frame> {
+        let offset = self.offset(tick_id, objects);
+        let drawn = (offset, terminal_size.width(), terminal_size.height());
+        if self.drawn == Some(drawn) {
+            return None;
+        }
+        self.drawn = Some(drawn);
+        Some(self.render(offset, terminal_size))
+    }
+}
@@ -1,4 +1,5 @@
 use crate::engine_v2::entity::frame::Frame;
+use crate::engine_v2::entity::sprite_animation::FrameContext;
 use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
 use crate::engine_v2::size::Size;
 
@@ -46,6 +47,10 @@ impl Sprite {
         self.animation.set_frame_id(frame_id);
     }
 
+    pub fn frame_index(&self) -> usize {
+        self.animation.current_frame_index()
+    }
+
     pub fn advance(
         &mut self,
         tick_id: usize,
@@ -62,4 +67,9 @@ impl Sprite {
             movement_ended,
         );
     }
+
+    /// Draws the frame of a procedural animation for the tick, returns `true` when it was drawn.
+    pub fn generate(&mut self, context: &FrameContext) -> bool {
+        self.animation.generate(context)
+    }
 }
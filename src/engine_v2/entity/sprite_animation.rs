@@ -1,3 +1,7 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::engine_v2::coords::Coords;
 use crate::engine_v2::entity::frame::Frame;
 
 #[derive(Debug, Clone, Copy)]
@@ -5,6 +9,49 @@ pub enum AnimationType {
     Static,
     TickBased,
     MovementBased,
+    Procedural,
+}
+
+/// What a procedural animation knows about its object when drawing a frame,
+/// see [`SpriteAnimation::new_procedural`].
+///
+/// ```rust
+/// use coretilus::engine_v2::coords::Coords;
+/// use coretilus::engine_v2::entity::frame::Frame;
+/// use coretilus::engine_v2::entity::sprite_animation::FrameContext;
+/// use coretilus::engine_v2::entity::sprite_animation::SpriteAnimation;
+///
+/// // A progress bar filling up in 40 ticks
+/// let mut animation = SpriteAnimation::new_procedural(|context| {
+///     let done = (context.tick_id / 10).min(4);
+///     Frame::new(format!("[{}{}]", "#".repeat(done), ".".repeat(4 - done)))
+/// });
+/// assert_eq!(animation.current_frame().content(), "[....]");
+///
+/// let context = FrameContext {
+///     tick_id: 25,
+///     coords: Coords::new(0, 0, 0),
+///     is_moving: false,
+/// };
+/// animation.generate(&context);
+/// assert_eq!(animation.current_frame().content(), "[##..]");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FrameContext {
+    pub tick_id: usize,
+    pub coords: Coords,
+    /// The movement of the object is active and not done
+    pub is_moving: bool,
+}
+
+/// Draws the frame of a procedural animation.
+#[derive(Clone)]
+struct FrameGenerator(Arc<dyn Fn(&FrameContext) -> Frame + Send + Sync>);
+
+impl fmt::Debug for FrameGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FrameGenerator")
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +68,8 @@ pub struct SpriteAnimation {
     started_tick_id: usize,
     only_when_movement_active: bool,
     stop_after_movement: bool,
+    // Frame drawn each tick for Procedural
+    generator: Option<FrameGenerator>,
 }
 
 impl SpriteAnimation {
@@ -37,6 +86,7 @@ impl SpriteAnimation {
             started_tick_id: 0,
             only_when_movement_active: false,
             stop_after_movement: false,
+            generator: None,
         }
     }
 
@@ -61,6 +111,7 @@ impl SpriteAnimation {
             started_tick_id: 0,
             only_when_movement_active,
             stop_after_movement,
+            generator: None,
         }
     }
 
@@ -77,6 +128,24 @@ impl SpriteAnimation {
             started_tick_id: 0,
             only_when_movement_active: true,
             stop_after_movement: true,
+            generator: None,
+        }
+    }
+
+    /// Constructor for a Procedural animation, its frame is drawn by `generator` each tick.
+    /// The first frame is drawn right away for the tick 0, at the origin.
+    pub fn new_procedural(
+        generator: impl Fn(&FrameContext) -> Frame + Send + Sync + 'static,
+    ) -> Self {
+        let first_frame = generator(&FrameContext {
+            tick_id: 0,
+            coords: Coords::new(0, 0, 0),
+            is_moving: false,
+        });
+        Self {
+            generator: Some(FrameGenerator(Arc::new(generator))),
+            animation_type: AnimationType::Procedural,
+            ..Self::new_static(first_frame)
         }
     }
 
@@ -115,7 +184,7 @@ impl SpriteAnimation {
         movement_ended: bool,
     ) {
         match self.animation_type {
-            AnimationType::Static => (),
+            AnimationType::Static | AnimationType::Procedural => (),
 
             AnimationType::TickBased => {
                 if self.stop_after_movement && movement_ended {
@@ -139,6 +208,16 @@ impl SpriteAnimation {
         }
     }
 
+    /// Draws the frame of a Procedural animation, the other animations are left untouched.
+    /// Returns `true` when a frame was drawn.
+    pub fn generate(&mut self, context: &FrameContext) -> bool {
+        let Some(generator) = &self.generator else {
+            return false;
+        };
+        self.frames[0] = (generator.0)(context);
+        true
+    }
+
     fn next_frame(&mut self) {
         if self.current_frame_index + 1 < self.frames.len() {
             self.current_frame_index += 1;
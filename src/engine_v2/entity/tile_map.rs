@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use crate::engine_v2::coords::Coords;
+use crate::engine_v2::entity::frame::Frame;
+use crate::engine_v2::size::Size;
+
+/// A property of a tile, see [`Tile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileProperty {
+    /// Objects should not go through the tile
+    Solid,
+    /// The tile is part of a path objects can follow
+    Path,
+    /// Something happens when an object reaches the tile
+    Trigger,
+}
+
+/// What a character of a tile map stands for: how it is drawn and its properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tile {
+    glyph: char,
+    solid: bool,
+    path: bool,
+    trigger: Option<String>,
+}
+
+impl Tile {
+    /// Creates a tile drawn with `glyph`, without any property.
+    pub fn new(glyph: char) -> Self {
+        Self {
+            glyph,
+            solid: false,
+            path: false,
+            trigger: None,
+        }
+    }
+
+    pub fn solid(mut self) -> Self {
+        self.solid = true;
+        self
+    }
+
+    pub fn path(mut self) -> Self {
+        self.path = true;
+        self
+    }
+
+    /// Names the trigger of the tile, so the collision callbacks can tell the triggers apart.
+    pub fn trigger(mut self, name: &str) -> Self {
+        self.trigger = Some(name.to_string());
+        self
+    }
+
+    pub fn glyph(&self) -> char {
+        self.glyph
+    }
+
+    pub fn is_solid(&self) -> bool {
+        self.solid
+    }
+
+    pub fn is_path(&self) -> bool {
+        self.path
+    }
+
+    pub fn trigger_name(&self) -> Option<&str> {
+        self.trigger.as_deref()
+    }
+
+    pub fn has(&self, property: TileProperty) -> bool {
+        match property {
+            TileProperty::Solid => self.solid,
+            TileProperty::Path => self.path,
+            TileProperty::Trigger => self.trigger.is_some(),
+        }
+    }
+}
+
+/// A level described as a grid of characters, each one standing for a [`Tile`] of the legend.
+///
+/// The map is drawn as a single frame, see
+/// [`Object::new_tile_map`](crate::engine_v2::entity::object::Object::new_tile_map).
+/// Like the scene, tile coordinates start at the bottom left corner of the map and `y` goes up.
+/// Spaces are empty cells, other characters missing from the legend are refused.
+///
+/// ```rust
+/// use coretilus::engine_v2::coords::Coords;
+/// use coretilus::engine_v2::entity::tile_map::Tile;
+/// use coretilus::engine_v2::entity::tile_map::TileMap;
+///
+/// let legend = [
+///     ('X', Tile::new('█').solid()),
+///     ('.', Tile::new('─').path()),
+///     ('|', Tile::new('│').path()),
+///     ('C', Tile::new('▣').path().trigger("cpu")),
+/// ];
+/// let map = TileMap::parse(
+///     "
+/// XXXXX
+/// ...CX
+/// |  XX",
+///     &legend,
+/// )
+/// .unwrap();
+/// assert_eq!((map.size().width(), map.size().height()), (5, 3));
+/// assert!(map.tile_at(0, 2).unwrap().is_solid());
+/// assert_eq!(map.tile_at(3, 1).unwrap().trigger_name(), Some("cpu"));
+/// assert_eq!(map.find_trigger("cpu"), Some(Coords::new(3, 1, 0)));
+/// assert!(map.tile_at(1, 0).is_none());
+/// assert_eq!(map.to_frame().content(), "█████\n───▣█\n│  ██");
+///
+/// // Paths only go through the path tiles
+/// let path = map.find_path(Coords::new(0, 0, 0), Coords::new(3, 1, 0)).unwrap();
+/// assert_eq!(path.len(), 5);
+/// assert_eq!(path[1], Coords::new(0, 1, 0));
+/// assert!(map.find_path(Coords::new(0, 0, 0), Coords::new(4, 1, 0)).is_none());
+///
+/// assert!(TileMap::parse("?", &legend).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TileMap {
+    size: Size,
+    // Rows from the top of the map, like the text grid
+    rows: Vec<Vec<Option<Tile>>>,
+}
+
+impl TileMap {
+    /// Reads the grid, empty lines at the start are ignored like in frames.
+    pub fn parse(grid: &str, legend: &[(char, Tile)]) -> Result<Self, String> {
+        let legend: HashMap<char, &Tile> = legend.iter().map(|(key, tile)| (*key, tile)).collect();
+        let lines: Vec<&str> = grid.trim_start_matches('\n').lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut rows = Vec::with_capacity(lines.len());
+        for (line_number, line) in lines.iter().enumerate() {
+            let mut row = Vec::with_capacity(width);
+            for char in line.chars() {
+                match (char, legend.get(&char)) {
+                    (_, Some(tile)) => row.push(Some((*tile).clone())),
+                    (' ', None) => row.push(None),
+                    (_, None) => {
+                        return Err(format!(
+                            "unknown tile '{}' on line {}",
+                            char,
+                            line_number + 1
+                        ));
+                    }
+                }
+            }
+            row.resize(width, None);
+            rows.push(row);
+        }
+        Ok(Self {
+            size: Size::new(width as u32, rows.len() as u32),
+            rows,
+        })
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Returns the tile at a position of the map, `None` for empty cells and outside of the map.
+    pub fn tile_at(&self, x: i32, y: i32) -> Option<&Tile> {
+        if x < 0 || y < 0 || x >= self.size.width() as i32 || y >= self.size.height() as i32 {
+            return None;
+        }
+        let row = self.size.height() as i32 - 1 - y;
+        self.rows[row as usize][x as usize].as_ref()
+    }
+
+    /// Returns `true` when the tile at the position has the property.
+    pub fn has(&self, x: i32, y: i32, property: TileProperty) -> bool {
+        self.tile_at(x, y).is_some_and(|tile| tile.has(property))
+    }
+
+    /// Returns the positions of the tiles with the property.
+    pub fn positions(&self, property: TileProperty) -> Vec<Coords> {
+        let mut positions = Vec::new();
+        for y in 0..self.size.height() as i32 {
+            for x in 0..self.size.width() as i32 {
+                if self.has(x, y, property) {
+                    positions.push(Coords::new(x, y, 0));
+                }
+            }
+        }
+        positions
+    }
+
+    /// Returns the position of the first tile whose trigger is called `name`,
+    /// from the bottom left corner.
+    pub fn find_trigger(&self, name: &str) -> Option<Coords> {
+        self.positions(TileProperty::Trigger)
+            .into_iter()
+            .find(|coords| {
+                self.tile_at(coords.x(), coords.y())
+                    .is_some_and(|tile| tile.trigger_name() == Some(name))
+            })
+    }
+
+    /// Returns the shortest way from `start` to `end` through path tiles, both included.
+    /// The positions are in the map, moving horizontally or vertically by one tile.
+    pub fn find_path(&self, start: Coords, end: Coords) -> Option<Vec<Coords>> {
+        let walkable = |coords: Coords| self.has(coords.x(), coords.y(), TileProperty::Path);
+        if !walkable(start) || !walkable(end) {
+            return None;
+        }
+        // Breadth first search, remembering where each tile was reached from
+        let mut previous: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+        let mut queue = VecDeque::from([(start.x(), start.y())]);
+        previous.insert((start.x(), start.y()), (start.x(), start.y()));
+        while let Some((x, y)) = queue.pop_front() {
+            if (x, y) == (end.x(), end.y()) {
+                let mut path = vec![Coords::new(x, y, start.z())];
+                let mut current = (x, y);
+                while current != (start.x(), start.y()) {
+                    current = previous[&current];
+                    path.push(Coords::new(current.0, current.1, start.z()));
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                if !previous.contains_key(&next) && walkable(Coords::new(next.0, next.1, 0)) {
+                    previous.insert(next, (x, y));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Draws the whole map as one frame, empty cells are spaces.
+    pub fn to_frame(&self) -> Frame {
+        let lines: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|tile| tile.as_ref().map_or(' ', Tile::glyph))
+                    .collect()
+            })
+            .collect();
+        Frame::new(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> TileMap {
+        let legend = [
+            ('X', Tile::new('#').solid()),
+            ('.', Tile::new('.').path()),
+            ('C', Tile::new('C').path().trigger("cpu")),
+        ];
+        TileMap::parse("X.C\n.\nXX", &legend).unwrap()
+    }
+
+    #[test]
+    fn test_tile_at() {
+        let map = map();
+        assert_eq!((map.size().width(), map.size().height()), (3, 3));
+        // The last line is the bottom of the map
+        assert_eq!(map.tile_at(0, 0).map(Tile::glyph), Some('#'));
+        assert_eq!(map.tile_at(0, 2).map(Tile::glyph), Some('#'));
+        assert_eq!(map.tile_at(2, 2).map(Tile::glyph), Some('C'));
+        // Short lines are padded with empty cells
+        assert!(map.tile_at(2, 0).is_none());
+        assert!(map.tile_at(1, 1).is_none());
+        // Outside of the map
+        assert!(map.tile_at(-1, 0).is_none());
+        assert!(map.tile_at(0, 3).is_none());
+        assert!(map.tile_at(3, 2).is_none());
+    }
+
+    #[test]
+    fn test_properties() {
+        let map = map();
+        assert!(map.has(0, 0, TileProperty::Solid));
+        assert!(!map.has(0, 0, TileProperty::Path));
+        assert!(map.has(2, 2, TileProperty::Path));
+        assert!(map.has(2, 2, TileProperty::Trigger));
+        assert!(!map.has(1, 1, TileProperty::Solid));
+        assert_eq!(
+            map.positions(TileProperty::Path),
+            vec![
+                Coords::new(0, 1, 0),
+                Coords::new(1, 2, 0),
+                Coords::new(2, 2, 0)
+            ]
+        );
+        assert_eq!(map.find_trigger("cpu"), Some(Coords::new(2, 2, 0)));
+        assert_eq!(map.find_trigger("ram"), None);
+    }
+}
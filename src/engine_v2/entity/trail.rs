@@ -0,0 +1,96 @@
+use crate::engine_v2::coords::Coords;
+use crate::engine_v2::entity::effect::DENSITY_RAMP;
+use crate::engine_v2::entity::effect::fade;
+
+/// Ghost glyphs left by an object at its previous positions, see
+/// [`Object::set_trail`](crate::engine_v2::entity::object::Object::set_trail).
+///
+/// By default the whole frame is left behind, lighter and lighter through the density ramp.
+/// With [`Trail::from_cell`] a single cell of the frame leaves the characters of the ramp,
+/// like the exhaust of a rocket. The trail is drawn under the object.
+///
+/// ```rust
+/// use coretilus::engine_v2::entity::trail::Trail;
+///
+/// let trail = Trail::new(4);
+/// // The newest ghost is the densest
+/// assert_eq!(trail.glyph('(', 0), '#');
+/// assert_eq!(trail.glyph('(', 3), ':');
+/// // Lighter glyphs are never made denser
+/// assert_eq!(trail.glyph('.', 0), '.');
+///
+/// let trail = Trail::new(2).from_cell(1, 0).with_ramp("*. ");
+/// assert_eq!(trail.cell(), Some((1, 0)));
+/// assert_eq!(trail.glyph('|', 0), '*');
+/// assert_eq!(trail.glyph('|', 1), '.');
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trail {
+    length: usize,
+    ramp: Vec<char>,
+    // Cell of the frame leaving the trail, from the bottom left corner
+    cell: Option<(i32, i32)>,
+}
+
+impl Trail {
+    /// Leaves the frame at the `length` previous positions of the object.
+    pub fn new(length: usize) -> Self {
+        Self {
+            length,
+            ramp: DENSITY_RAMP.chars().collect(),
+            cell: None,
+        }
+    }
+
+    /// Only the cell `(x, y)` of the frame leaves a trail, drawn with the ramp.
+    pub fn from_cell(mut self, x: i32, y: i32) -> Self {
+        self.cell = Some((x, y));
+        self
+    }
+
+    /// Replaces the density ramp, from the densest character to a space.
+    pub fn with_ramp(mut self, ramp: &str) -> Self {
+        self.ramp = ramp.chars().collect();
+        if self.ramp.last() != Some(&' ') {
+            self.ramp.push(' ');
+        }
+        self
+    }
+
+    /// Returns the number of previous positions the trail is drawn at.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn cell(&self) -> Option<(i32, i32)> {
+        self.cell
+    }
+
+    /// Returns the ghost of `glyph` at the `age`-th previous position, 0 being the newest.
+    pub fn glyph(&self, glyph: char, age: usize) -> char {
+        // The oldest ghost is still drawn, the newest is lighter than the object
+        let amount = (age + 1) as f64 / (self.length + 1) as f64;
+        match self.cell {
+            // The ramp without its space, from the newest to the oldest
+            Some(_) => {
+                let age = age.min(self.length.saturating_sub(1));
+                self.ramp[age * (self.ramp.len() - 1) / self.length.max(1)]
+            }
+            None => fade(glyph, amount, &self.ramp),
+        }
+    }
+
+    /// Returns the positions the trail is drawn at from the newest,
+    /// `history` being the previous positions of the object from the oldest.
+    pub(crate) fn positions<'a>(
+        &self,
+        coords: Coords,
+        history: impl DoubleEndedIterator<Item = &'a Coords>,
+    ) -> impl Iterator<Item = Coords> {
+        history
+            .rev()
+            .filter(move |previous| (previous.x(), previous.y()) != (coords.x(), coords.y()))
+            .take(self.length)
+            .copied()
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::io::stderr;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
+use crate::engine_v2::entity::object::Object;
+
+/// Environment variable naming the file receiving the engine events.
+pub const LOG_ENV_VAR: &str = "CORETILUS_LOG";
+
+// Opened once per process, the engines of a command write to the same file
+static LOG_FILE: OnceLock<Option<Mutex<BufWriter<File>>>> = OnceLock::new();
+
+/// Why an engine stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The command called [`Engine::stop`](crate::engine_v2::engine::Engine::stop)
+    Stopped,
+    /// The number of ticks to live was reached
+    TimeToLive,
+    /// `--max-duration` was reached
+    MaxDuration,
+    /// Ctrl+C was pressed
+    Interrupted,
+    /// A termination signal was received
+    Signal(i32),
+    /// The recorded run being replayed ended
+    ReplayEnded,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Stopped => write!(f, "stopped"),
+            StopReason::TimeToLive => write!(f, "ttl"),
+            StopReason::MaxDuration => write!(f, "max_duration"),
+            StopReason::Interrupted => write!(f, "interrupted"),
+            StopReason::Signal(sig) => write!(f, "signal_{}", sig),
+            StopReason::ReplayEnded => write!(f, "replay_ended"),
+        }
+    }
+}
+
+/// What is compared between two ticks to detect the changes of an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ObjectState {
+    visible: bool,
+    movement_active: bool,
+    movement_started: bool,
+    movement_done: bool,
+    sprite_active: bool,
+    sprite_index: usize,
+    frame_index: usize,
+}
+
+impl ObjectState {
+    fn new(object: &Object) -> Self {
+        Self {
+            visible: object.visible(),
+            movement_active: object.is_movement_active(),
+            movement_started: object.movement().has_started(),
+            movement_done: object.movement().is_done(),
+            sprite_active: object.is_sprite_active(),
+            sprite_index: object.active_sprite(),
+            frame_index: object.frame_index(),
+        }
+    }
+}
+
+/// Structured log of what happens in the engine, one `key=value` line per event.
+///
+/// It is enabled by setting `CORETILUS_LOG` to a file path, it is never written
+/// to the terminal the animation is drawn on. Object changes are found by comparing
+/// the objects at the end of each tick with the previous tick.
+///
+/// ```rust
+/// use coretilus::engine_v2::event_log::EventLog;
+///
+/// let mut log = EventLog::new(None);
+/// assert!(!log.is_enabled());
+/// // Nothing is written, nor formatted, when the log is disabled
+/// log.event(1, "stop", &[("reason", String::from("ttl"))]);
+/// ```
+#[derive(Default)]
+pub struct EventLog {
+    file: Option<&'static Mutex<BufWriter<File>>>,
+    states: HashMap<ObjectHandle, ObjectState>,
+}
+
+impl EventLog {
+    pub fn new(file: Option<&'static Mutex<BufWriter<File>>>) -> Self {
+        Self {
+            file,
+            states: HashMap::new(),
+        }
+    }
+
+    /// Uses the file named by `CORETILUS_LOG`, the log is disabled when it is not set
+    /// or when the file can't be opened.
+    pub fn from_env() -> Self {
+        let file = LOG_FILE.get_or_init(|| {
+            let path = std::env::var_os(LOG_ENV_VAR).filter(|path| !path.is_empty())?;
+            let file = File::create(&path)
+                .inspect_err(|error| {
+                    let _ = writeln!(stderr(), "cannot open {:?}: {}", path, error);
+                })
+                .ok()?;
+            if file.is_terminal() {
+                // It would garble the animation
+                let _ = writeln!(stderr(), "{} can't be a terminal", LOG_ENV_VAR);
+                return None;
+            }
+            Some(Mutex::new(BufWriter::new(file)))
+        });
+        Self::new(file.as_ref())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.file.is_some()
+    }
+
+    /// Writes an event with its fields, values are quoted when they contain spaces.
+    pub fn event(&mut self, tick_id: usize, event: &str, fields: &[(&str, String)]) {
+        let Some(file) = self.file else {
+            return;
+        };
+        let mut line = format!("tick={} event={}", tick_id, event);
+        for (key, value) in fields {
+            if value.is_empty() || value.contains([' ', '"', '=']) {
+                line.push_str(&format!(" {}={:?}", key, value));
+            } else {
+                line.push_str(&format!(" {}={}", key, value));
+            }
+        }
+        let _ = writeln!(file.lock().unwrap(), "{}", line);
+    }
+
+    /// Writes an event about an object, identified by its handle, TypoDex id and name.
+    pub fn object_event(
+        &mut self,
+        tick_id: usize,
+        event: &str,
+        handle: ObjectHandle,
+        object: Option<&Object>,
+        fields: &[(&str, String)],
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut all_fields = vec![("object", handle.index().to_string())];
+        if let Some(object) = object {
+            all_fields.push(("tdid", object.tdid().to_string()));
+            all_fields.push(("name", object.tdname()));
+        }
+        all_fields.extend_from_slice(fields);
+        self.event(tick_id, event, &all_fields);
+    }
+
+    /// Compares the objects with the previous tick and writes their changes.
+    pub fn diff_objects(&mut self, tick_id: usize, objects: &ObjectArena) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut previous_states = std::mem::take(&mut self.states);
+        for (handle, object) in objects.iter() {
+            let state = ObjectState::new(object);
+            match previous_states.remove(&handle) {
+                None => self.object_event(tick_id, "spawn", handle, Some(object), &[]),
+                Some(previous) if previous != state => {
+                    self.log_changes(tick_id, handle, object, previous, state)
+                }
+                Some(_) => {}
+            }
+            self.states.insert(handle, state);
+        }
+        for handle in previous_states.into_keys() {
+            self.object_event(tick_id, "remove", handle, None, &[]);
+        }
+    }
+
+    fn log_changes(
+        &mut self,
+        tick_id: usize,
+        handle: ObjectHandle,
+        object: &Object,
+        previous: ObjectState,
+        state: ObjectState,
+    ) {
+        let on_off = |value: bool| String::from(if value { "on" } else { "off" });
+        if !previous.movement_started && state.movement_started {
+            self.object_event(tick_id, "movement_start", handle, Some(object), &[]);
+        }
+        if !previous.movement_done && state.movement_done {
+            self.object_event(tick_id, "movement_done", handle, Some(object), &[]);
+        }
+        if previous.movement_active != state.movement_active {
+            let fields = [("state", on_off(state.movement_active))];
+            self.object_event(tick_id, "movement_active", handle, Some(object), &fields);
+        }
+        if previous.visible != state.visible {
+            let fields = [("state", on_off(state.visible))];
+            self.object_event(tick_id, "visible", handle, Some(object), &fields);
+        }
+        if previous.sprite_active != state.sprite_active {
+            let fields = [("state", on_off(state.sprite_active))];
+            self.object_event(tick_id, "sprite_active", handle, Some(object), &fields);
+        }
+        if previous.sprite_index != state.sprite_index {
+            let fields = [("index", state.sprite_index.to_string())];
+            self.object_event(tick_id, "sprite", handle, Some(object), &fields);
+        }
+        if previous.frame_index != state.frame_index {
+            let fields = [("frame", state.frame_index.to_string())];
+            self.object_event(tick_id, "frame", handle, Some(object), &fields);
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if let Some(file) = self.file {
+            let _ = file.lock().unwrap().flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_v2::entity::frame::Frame;
+    use crate::engine_v2::entity::sprite::Sprite;
+    use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
+
+    #[test]
+    fn test_log_sprite_change() {
+        let path = std::env::temp_dir().join(format!("coretilus-log-{}.log", std::process::id()));
+        let file = Box::leak(Box::new(Mutex::new(BufWriter::new(
+            File::create(&path).unwrap(),
+        ))));
+        let mut log = EventLog::new(Some(file));
+
+        let sprites = vec![
+            Sprite::new(SpriteAnimation::new_static(Frame::new("o")), true),
+            Sprite::new(SpriteAnimation::new_static(Frame::new("O")), true),
+        ];
+        let mut objects = ObjectArena::new();
+        let ball = objects.insert(Object::new(7, String::from("ball"), sprites, None));
+        log.diff_objects(1, &objects);
+        log.diff_objects(2, &objects);
+        objects[ball].set_active_sprite(1);
+        log.diff_objects(3, &objects);
+        // Out of the sprites, nothing changes
+        objects[ball].set_active_sprite(2);
+        log.diff_objects(4, &objects);
+        log.flush();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "tick=1 event=spawn object=0 tdid=7 name=ball",
+                "tick=3 event=sprite object=0 tdid=7 name=ball index=1",
+            ]
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
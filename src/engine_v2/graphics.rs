@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+
+use crossterm::terminal::window_size;
+
+use crate::engine_v2::size::Size;
+
+/// Size of the base64 chunks sent with the kitty graphics protocol.
+const KITTY_CHUNK_SIZE: usize = 4096;
+/// Size of a cell in pixels, when the terminal doesn't report it.
+const DEFAULT_CELL_PIXELS: (u32, u32) = (10, 20);
+
+// Kitty image ids, they must be unique in the process
+static NEXT_BITMAP_ID: AtomicU32 = AtomicU32::new(1);
+
+/// A protocol drawing bitmaps in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// The kitty graphics protocol, also spoken by WezTerm and Ghostty
+    Kitty,
+    /// DEC Sixel
+    Sixel,
+}
+
+/// An RGBA image drawn instead of the frame of an object, on terminals supporting graphics.
+///
+/// The frame of the object stays its fallback and keeps giving its size in cells:
+/// positions and collisions don't change with the graphics.
+///
+/// ```rust
+/// use coretilus::engine_v2::graphics::Bitmap;
+///
+/// let bitmap = Bitmap::from_pixel_art(
+///     "
+/// .r.
+/// rgr",
+///     &[('r', [255, 0, 0, 255]), ('g', [0, 255, 0, 255])],
+/// );
+/// assert_eq!((bitmap.width(), bitmap.height()), (3, 2));
+/// assert_eq!(bitmap.pixel(1, 0), [255, 0, 0, 255]);
+/// // Characters missing from the palette are transparent
+/// assert_eq!(bitmap.pixel(0, 0), [0, 0, 0, 0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bitmap {
+    id: u32,
+    width: u32,
+    height: u32,
+    // Pixels from the top left, row by row, 4 bytes each
+    rgba: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Creates a bitmap from its RGBA pixels, from the top left row by row.
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Self {
+        assert_eq!(
+            rgba.len(),
+            (width * height * 4) as usize,
+            "the pixels don't match the size of the bitmap"
+        );
+        Self {
+            id: NEXT_BITMAP_ID.fetch_add(1, Ordering::Relaxed),
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    /// Creates a bitmap from text, one character per pixel colored with `palette`.
+    /// Empty lines at the start are ignored, like in frames.
+    pub fn from_pixel_art(art: &str, palette: &[(char, [u8; 4])]) -> Self {
+        let lines: Vec<&str> = art.trim_start_matches('\n').lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut rgba = Vec::with_capacity(width * lines.len() * 4);
+        for line in lines.iter() {
+            let mut chars = line.chars();
+            for _ in 0..width {
+                let color = chars
+                    .next()
+                    .and_then(|char| palette.iter().find(|(key, _)| *key == char))
+                    .map(|(_, color)| *color)
+                    .unwrap_or([0, 0, 0, 0]);
+                rgba.extend_from_slice(&color);
+            }
+        }
+        Self::new(width as u32, lines.len() as u32, rgba)
+    }
+
+    /// Identifies the bitmap, so it is sent to the terminal only once.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * self.width + x) * 4) as usize;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.rgba[index..index + 4]);
+        pixel
+    }
+
+    /// Returns a copy of the bitmap resized to `width` x `height` pixels, pixels are repeated
+    /// or skipped so the pixel art stays sharp.
+    ///
+    /// ```rust
+    /// use coretilus::engine_v2::graphics::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_pixel_art("rg", &[('r', [255, 0, 0, 255]), ('g', [0, 255, 0, 255])]);
+    /// let scaled = bitmap.scaled(4, 2);
+    /// assert_eq!((scaled.width(), scaled.height()), (4, 2));
+    /// assert_eq!(scaled.pixel(1, 1), [255, 0, 0, 255]);
+    /// assert_eq!(scaled.pixel(2, 0), [0, 255, 0, 255]);
+    /// ```
+    pub fn scaled(&self, width: u32, height: u32) -> Bitmap {
+        let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                rgba.extend_from_slice(
+                    &self.pixel(x * self.width / width, y * self.height / height),
+                );
+            }
+        }
+        Bitmap::new(width, height, rgba)
+    }
+}
+
+/// Returns the size of a terminal cell in pixels, or a usual size when the terminal doesn't tell.
+pub fn cell_pixel_size() -> Size {
+    match window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            Size::new(
+                (size.width / size.columns) as u32,
+                (size.height / size.rows) as u32,
+            )
+        }
+        _ => Size::new(DEFAULT_CELL_PIXELS.0, DEFAULT_CELL_PIXELS.1),
+    }
+}
+
+/// Sends a bitmap to the terminal with the kitty graphics protocol, without showing it.
+///
+/// ```rust
+/// use coretilus::engine_v2::graphics::Bitmap;
+/// use coretilus::engine_v2::graphics::kitty_transmit;
+///
+/// let bitmap = Bitmap::new(1, 1, vec![255, 0, 0, 255]);
+/// let sequence = kitty_transmit(&bitmap);
+/// let expected = format!("\x1b_Ga=t,f=32,s=1,v=1,i={},q=2,m=0;/wAA/w==\x1b\\", bitmap.id());
+/// assert_eq!(sequence, expected);
+/// ```
+pub fn kitty_transmit(bitmap: &Bitmap) -> String {
+    let data = base64(&bitmap.rgba);
+    // The data is split in chunks, only the first one carries the keys
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut sequence = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = usize::from(index + 1 < chunks.len());
+        sequence.push_str("\x1b_G");
+        if index == 0 {
+            sequence.push_str(&format!(
+                "a=t,f=32,s={},v={},i={},q=2,",
+                bitmap.width, bitmap.height, bitmap.id
+            ));
+        }
+        sequence.push_str(&format!("m={};", more));
+        // Base64 is ASCII
+        sequence.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        sequence.push_str("\x1b\\");
+    }
+    sequence
+}
+
+/// Shows a bitmap sent with [`kitty_transmit`] at the cursor, stretched over `cells`.
+///
+/// ```rust
+/// use coretilus::engine_v2::graphics::kitty_place;
+/// use coretilus::engine_v2::size::Size;
+///
+/// assert_eq!(kitty_place(7, Size::new(4, 2)), "\x1b_Ga=p,i=7,c=4,r=2,C=1,q=2\x1b\\");
+/// ```
+pub fn kitty_place(id: u32, cells: Size) -> String {
+    format!(
+        "\x1b_Ga=p,i={},c={},r={},C=1,q=2\x1b\\",
+        id,
+        cells.width(),
+        cells.height()
+    )
+}
+
+/// Removes every bitmap shown with the kitty graphics protocol,
+/// `free` also forgets the bitmaps sent.
+pub fn kitty_clear(free: bool) -> &'static str {
+    match free {
+        true => "\x1b_Ga=d,d=A,q=2\x1b\\",
+        false => "\x1b_Ga=d,d=a,q=2\x1b\\",
+    }
+}
+
+/// Draws a bitmap at the cursor with Sixel, transparent pixels leave the cells untouched.
+///
+/// The colors are quantized when the bitmap has more than 256 of them.
+/// Sixel draws at the real size of the bitmap, it must be [scaled](Bitmap::scaled)
+/// to the pixels of the cells it covers first.
+///
+/// ```rust
+/// use coretilus::engine_v2::graphics::Bitmap;
+/// use coretilus::engine_v2::graphics::sixel;
+///
+/// // A red pixel on top of a transparent one, then a column of 8 blue pixels
+/// let red = [255, 0, 0, 255];
+/// let blue = [0, 0, 255, 255];
+/// let mut rgba = Vec::new();
+/// for y in 0..8 {
+///     let left = if y == 0 { red } else { [0; 4] };
+///     rgba.extend_from_slice(&left);
+///     rgba.extend_from_slice(&blue);
+/// }
+/// let bitmap = Bitmap::new(2, 8, rgba);
+/// assert_eq!(
+///     sixel(&bitmap),
+///     "\x1bP0;1;0q\"1;1;2;8#0;2;100;0;0#1;2;0;0;100#0@?$#1?~$-#1?B$\x1b\\"
+/// );
+/// ```
+pub fn sixel(bitmap: &Bitmap) -> String {
+    let mut pixels: Vec<Option<[u8; 3]>> = bitmap
+        .rgba
+        .chunks_exact(4)
+        .map(|pixel| (pixel[3] >= 128).then(|| [pixel[0], pixel[1], pixel[2]]))
+        .collect();
+    let distinct_colors: HashSet<[u8; 3]> = pixels.iter().flatten().copied().collect();
+    if distinct_colors.len() > 256 {
+        // 6 levels per channel, like the xterm color cube
+        let quantize = |value: u8| ((value as u32 * 5 + 127) / 255 * 51) as u8;
+        for color in pixels.iter_mut().flatten() {
+            *color = color.map(quantize);
+        }
+    }
+    // Palette registers, in the order the colors appear
+    let mut colors: Vec<[u8; 3]> = Vec::new();
+    let mut color_index: HashMap<[u8; 3], usize> = HashMap::new();
+    let pixels: Vec<Option<usize>> = pixels
+        .iter()
+        .map(|pixel| {
+            pixel.map(|color| {
+                *color_index.entry(color).or_insert_with(|| {
+                    colors.push(color);
+                    colors.len() - 1
+                })
+            })
+        })
+        .collect();
+
+    // P2=1 keeps the transparent pixels, the raster attributes give the size
+    let mut sequence = format!("\x1bP0;1;0q\"1;1;{};{}", bitmap.width, bitmap.height);
+    let percent = |value: u8| (value as u32 * 100 + 127) / 255;
+    for (index, color) in colors.iter().enumerate() {
+        sequence.push_str(&format!(
+            "#{};2;{};{};{}",
+            index,
+            percent(color[0]),
+            percent(color[1]),
+            percent(color[2])
+        ));
+    }
+
+    // Sixel rows are bands of 6 pixels, drawn color by color
+    let bands = bitmap.height.div_ceil(6);
+    for band in 0..bands {
+        if band > 0 {
+            sequence.push('-');
+        }
+        for index in 0..colors.len() {
+            let mut line: Vec<u8> = Vec::with_capacity(bitmap.width as usize);
+            for x in 0..bitmap.width {
+                let mut bits = 0;
+                for dy in 0..6 {
+                    let y = band * 6 + dy;
+                    if y < bitmap.height && pixels[(y * bitmap.width + x) as usize] == Some(index) {
+                        bits |= 1 << dy;
+                    }
+                }
+                line.push(0x3F + bits);
+            }
+            if line.iter().all(|&sixel| sixel == 0x3F) {
+                continue;
+            }
+            sequence.push_str(&format!("#{}", index));
+            push_run_length(&mut sequence, &line);
+            // Back to the start of the band for the next color
+            sequence.push('$');
+        }
+    }
+    sequence.push_str("\x1b\\");
+    sequence
+}
+
+/// Writes sixels, repeating the long runs with `!`.
+fn push_run_length(sequence: &mut String, line: &[u8]) {
+    let mut rest = line;
+    while let Some(&sixel) = rest.first() {
+        let run = rest.iter().take_while(|&&other| other == sixel).count();
+        if run > 3 {
+            sequence.push_str(&format!("!{}{}", run, sixel as char));
+        } else {
+            sequence.extend(std::iter::repeat_n(sixel as char, run));
+        }
+        rest = &rest[run..];
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let value = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for position in 0..4 {
+            if position <= chunk.len() {
+                encoded.push(ALPHABET[(value >> (18 - 6 * position) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// A bitmap to draw on the screen, its top left cell and the cells it covers.
+#[derive(Debug, Clone)]
+pub struct ImagePlacement {
+    pub bitmap: Arc<Bitmap>,
+    pub column: u16,
+    pub row: u16,
+    pub cells: Size,
+}
+
+/// Draws the bitmaps of the frames with a protocol, remembering what the terminal already has.
+pub(crate) struct GraphicsWriter {
+    protocol: GraphicsProtocol,
+    // Size of a cell in pixels, Sixel bitmaps are scaled to cover their cells
+    cell_pixels: Size,
+    // Kitty bitmaps already sent to the terminal
+    transmitted: HashSet<u32>,
+    // Sixel sequences by bitmap and size in pixels
+    sixels: HashMap<(u32, u32, u32), String>,
+}
+
+impl GraphicsWriter {
+    pub(crate) fn new(protocol: GraphicsProtocol, cell_pixels: Size) -> Self {
+        Self {
+            protocol,
+            cell_pixels,
+            transmitted: HashSet::new(),
+            sixels: HashMap::new(),
+        }
+    }
+
+    /// Escape sequences drawing the bitmaps of a frame, after its text.
+    /// Kitty bitmaps are sent once and stretched over their cells by the terminal.
+    pub(crate) fn encode_placements(&mut self, placements: &[ImagePlacement]) -> String {
+        let mut sequence = String::new();
+        if self.protocol == GraphicsProtocol::Kitty {
+            // Kitty bitmaps stay above the text, the ones of the previous frame are removed
+            sequence.push_str(kitty_clear(false));
+        }
+        for placement in placements {
+            sequence.push_str(&format!(
+                "\x1b[{};{}H",
+                placement.row + 1,
+                placement.column + 1
+            ));
+            let bitmap = &placement.bitmap;
+            match self.protocol {
+                GraphicsProtocol::Kitty => {
+                    if self.transmitted.insert(bitmap.id()) {
+                        sequence.push_str(&kitty_transmit(bitmap));
+                    }
+                    sequence.push_str(&kitty_place(bitmap.id(), placement.cells));
+                }
+                GraphicsProtocol::Sixel => {
+                    let width = placement.cells.width() * self.cell_pixels.width();
+                    let height = placement.cells.height() * self.cell_pixels.height();
+                    let encoded = self
+                        .sixels
+                        .entry((bitmap.id(), width, height))
+                        .or_insert_with(|| sixel(&bitmap.scaled(width, height)));
+                    sequence.push_str(encoded);
+                }
+            }
+        }
+        sequence
+    }
+
+    /// Escape sequences to send before giving the terminal back.
+    pub(crate) fn finish(&self) -> &'static str {
+        match self.transmitted.is_empty() {
+            // Free the bitmaps kept by the terminal
+            false => kitty_clear(true),
+            true => "",
+        }
+    }
+}
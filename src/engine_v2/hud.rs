@@ -0,0 +1,157 @@
+use crate::engine_v2::engine::Engine;
+
+/// Where a HUD widget is attached on the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// A text drawn over the scene, anchored to a corner or an edge of the screen.
+///
+/// The text is computed from the engine each time a frame is built,
+/// so it can follow live values like a score or the elapsed time.
+/// It can span several lines and its spaces hide the scene behind it.
+pub struct HudWidget {
+    anchor: Anchor,
+    // Distance from the anchored edges, in cells
+    margin: (u32, u32),
+    text: Box<dyn Fn(&Engine) -> String>,
+    visible: bool,
+}
+
+impl HudWidget {
+    pub fn new(anchor: Anchor, text: impl Fn(&Engine) -> String + 'static) -> Self {
+        Self {
+            anchor,
+            margin: (0, 0),
+            text: Box::new(text),
+            visible: true,
+        }
+    }
+
+    /// Creates a widget always showing the same text.
+    pub fn new_static(anchor: Anchor, text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self::new(anchor, move |_| text.clone())
+    }
+
+    pub fn with_margin(mut self, x: u32, y: u32) -> Self {
+        self.margin = (x, y);
+        self
+    }
+
+    pub fn anchor(&self) -> Anchor {
+        self.anchor
+    }
+
+    pub fn text(&self, engine: &Engine) -> String {
+        (self.text)(engine)
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, value: bool) {
+        self.visible = value;
+    }
+
+    /// Returns the screen column and row of the top left corner of a text of the given size.
+    fn origin(&self, screen_width: i32, screen_height: i32, width: i32, height: i32) -> (i32, i32) {
+        let (margin_x, margin_y) = (self.margin.0 as i32, self.margin.1 as i32);
+        let left = margin_x;
+        let center_x = (screen_width - width) / 2;
+        let right = screen_width - width - margin_x;
+        let top = margin_y;
+        let middle_y = (screen_height - height) / 2;
+        let bottom = screen_height - height - margin_y;
+        match self.anchor {
+            Anchor::TopLeft => (left, top),
+            Anchor::Top => (center_x, top),
+            Anchor::TopRight => (right, top),
+            Anchor::Left => (left, middle_y),
+            Anchor::Center => (center_x, middle_y),
+            Anchor::Right => (right, middle_y),
+            Anchor::BottomLeft => (left, bottom),
+            Anchor::Bottom => (center_x, bottom),
+            Anchor::BottomRight => (right, bottom),
+        }
+    }
+}
+
+/// The layer of widgets drawn above the scene.
+///
+/// ```rust
+/// use coretilus::engine_v2::arena::ObjectArena;
+/// use coretilus::engine_v2::engine::Engine;
+/// use coretilus::engine_v2::hud::Anchor;
+/// use coretilus::engine_v2::hud::Hud;
+/// use coretilus::engine_v2::hud::HudWidget;
+///
+/// let engine = Engine::new(ObjectArena::new(), Vec::new(), 0);
+/// let mut hud = Hud::new();
+/// hud.add(HudWidget::new_static(Anchor::TopLeft, "score"));
+/// hud.add(HudWidget::new(Anchor::BottomRight, |engine| format!("tick {}", engine.tick_id())));
+///
+/// let mut screen = vec![vec!['.'; 10]; 3];
+/// hud.draw(&mut screen, &engine);
+/// assert_eq!(screen[0].iter().collect::<String>(), "score.....");
+/// assert_eq!(screen[2].iter().collect::<String>(), "....tick 0");
+/// ```
+#[derive(Default)]
+pub struct Hud {
+    widgets: Vec<HudWidget>,
+}
+
+impl Hud {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a widget and returns its id.
+    pub fn add(&mut self, widget: HudWidget) -> usize {
+        self.widgets.push(widget);
+        self.widgets.len() - 1
+    }
+
+    pub fn widget_mut(&mut self, id: usize) -> Option<&mut HudWidget> {
+        self.widgets.get_mut(id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// Draws the visible widgets on top of an already built screen.
+    pub fn draw(&self, screen: &mut [Vec<char>], engine: &Engine) {
+        let screen_height = screen.len() as i32;
+        let screen_width = screen.first().map(|line| line.len()).unwrap_or(0) as i32;
+        for widget in self.widgets.iter().filter(|widget| widget.visible) {
+            let text = widget.text(engine);
+            let lines: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+            let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as i32;
+            let (x, y) = widget.origin(screen_width, screen_height, width, lines.len() as i32);
+            for (dy, line) in lines.iter().enumerate() {
+                let row = y + dy as i32;
+                if row < 0 || row >= screen_height {
+                    continue;
+                }
+                for (dx, char) in line.iter().enumerate() {
+                    let column = x + dx as i32;
+                    if column < 0 || column >= screen_width {
+                        continue;
+                    }
+                    screen[row as usize][column as usize] = *char;
+                }
+            }
+        }
+    }
+}
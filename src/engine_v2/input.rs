@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+
+/// Time a key stays down after a single press when the terminal doesn't report releases.
+/// It covers the usual delay before the keyboard starts repeating.
+const PRESS_HOLD_TIMEOUT: Duration = Duration::from_millis(600);
+/// Time a key stays down after a repeat when the terminal doesn't report releases.
+const REPEAT_HOLD_TIMEOUT: Duration = Duration::from_millis(150);
+/// Longest interval between two events of a key repeated by the keyboard,
+/// slower events are distinct presses when the terminal doesn't report releases.
+const REPEAT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The kind of key event an action is bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyTrigger {
+    /// The key goes down
+    Press,
+    /// The keyboard repeats the key while it is held
+    Repeat,
+    /// The key goes up
+    Release,
+    /// Every tick while the keyboard repeats the key
+    Held,
+}
+
+/// A key, its modifiers and the kind of event triggering an action.
+///
+/// Modifiers must match exactly, except `Shift` which is ignored for characters
+/// since the character already tells if it is uppercase.
+///
+/// ```rust
+/// use crossterm::event::KeyCode;
+/// use crossterm::event::KeyModifiers;
+/// use coretilus::engine_v2::input::KeyBinding;
+/// use coretilus::engine_v2::input::KeyTrigger;
+///
+/// let binding = KeyBinding::press(KeyCode::Char('a')).with_modifiers(KeyModifiers::CONTROL);
+/// assert!(binding.matches(KeyCode::Char('a'), KeyModifiers::CONTROL, KeyTrigger::Press));
+/// assert!(!binding.matches(KeyCode::Char('a'), KeyModifiers::NONE, KeyTrigger::Press));
+/// assert!(!binding.matches(KeyCode::Char('a'), KeyModifiers::CONTROL, KeyTrigger::Release));
+///
+/// let binding = KeyBinding::release(KeyCode::Char('A'));
+/// assert!(binding.matches(KeyCode::Char('A'), KeyModifiers::SHIFT, KeyTrigger::Release));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    trigger: KeyTrigger,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers, trigger: KeyTrigger) -> Self {
+        Self {
+            code,
+            modifiers,
+            trigger,
+        }
+    }
+
+    pub fn press(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE, KeyTrigger::Press)
+    }
+
+    pub fn repeat(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE, KeyTrigger::Repeat)
+    }
+
+    pub fn release(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE, KeyTrigger::Release)
+    }
+
+    pub fn held(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE, KeyTrigger::Held)
+    }
+
+    pub fn with_modifiers(mut self, modifiers: KeyModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn code(&self) -> KeyCode {
+        self.code
+    }
+
+    pub fn modifiers(&self) -> KeyModifiers {
+        self.modifiers
+    }
+
+    pub fn trigger(&self) -> KeyTrigger {
+        self.trigger
+    }
+
+    pub fn matches(&self, code: KeyCode, modifiers: KeyModifiers, trigger: KeyTrigger) -> bool {
+        if self.code != code || self.trigger != trigger {
+            return false;
+        }
+        let ignored = match code {
+            KeyCode::Char(_) => KeyModifiers::SHIFT,
+            _ => KeyModifiers::NONE,
+        };
+        self.modifiers.difference(ignored) == modifiers.difference(ignored)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct KeyState {
+    modifiers: KeyModifiers,
+    last_event_time: Instant,
+    repeating: bool,
+    // Keys are handled in the order they went down, so a replay dispatches them the same way
+    press_order: u64,
+}
+
+/// Keeps track of the keys currently down.
+///
+/// Terminals supporting the keyboard enhancement protocol report releases.
+/// With the others, a key is considered released when it wasn't pressed nor repeated for a while.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use std::time::Instant;
+/// use crossterm::event::KeyCode;
+/// use crossterm::event::KeyEvent;
+/// use crossterm::event::KeyModifiers;
+/// use coretilus::engine_v2::input::InputState;
+/// use coretilus::engine_v2::input::KeyTrigger;
+///
+/// let mut input = InputState::new();
+/// let now = Instant::now();
+/// let key = KeyEvent::new(KeyCode::Left, KeyModifiers::NONE);
+///
+/// assert_eq!(input.key_event(key, now), Some(KeyTrigger::Press));
+/// assert!(input.is_down(KeyCode::Left));
+/// assert!(!input.is_held(KeyCode::Left));
+///
+/// // The key is tapped again
+/// let tapped = now + Duration::from_millis(300);
+/// assert_eq!(input.key_event(key, tapped), Some(KeyTrigger::Press));
+///
+/// // The terminal repeats the key
+/// let later = tapped + Duration::from_millis(30);
+/// assert_eq!(input.key_event(key, later), Some(KeyTrigger::Repeat));
+/// assert!(input.is_held(KeyCode::Left));
+///
+/// // No more repeats, the key was released
+/// let released = input.expire(later + Duration::from_secs(1));
+/// assert_eq!(released, vec![(KeyCode::Left, KeyModifiers::NONE)]);
+/// assert!(!input.is_down(KeyCode::Left));
+///
+/// // Held keys come in the order they were pressed
+/// for code in [KeyCode::Char('z'), KeyCode::Up, KeyCode::Char('a')] {
+///     let key = KeyEvent::new(code, KeyModifiers::NONE);
+///     input.key_event(key, tapped);
+///     input.key_event(key, later);
+/// }
+/// let held: Vec<KeyCode> = input.held_keys().into_iter().map(|(code, _)| code).collect();
+/// assert_eq!(held, vec![KeyCode::Char('z'), KeyCode::Up, KeyCode::Char('a')]);
+/// ```
+#[derive(Debug, Default)]
+pub struct InputState {
+    keys: HashMap<KeyCode, KeyState>,
+    // Set once the terminal reported a release
+    reports_releases: bool,
+    presses: u64,
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` when the key is down.
+    pub fn is_down(&self, code: KeyCode) -> bool {
+        self.keys.contains_key(&code)
+    }
+
+    /// Returns `true` when the key is held long enough to be repeated.
+    pub fn is_held(&self, code: KeyCode) -> bool {
+        self.keys.get(&code).is_some_and(|key| key.repeating)
+    }
+
+    /// Returns the held keys with their modifiers, in the order they were pressed.
+    pub fn held_keys(&self) -> Vec<(KeyCode, KeyModifiers)> {
+        self.ordered_keys(|key| key.repeating)
+    }
+
+    /// Returns the keys matching `filter`, in the order they were pressed.
+    fn ordered_keys(&self, filter: impl Fn(&KeyState) -> bool) -> Vec<(KeyCode, KeyModifiers)> {
+        let mut keys: Vec<(&KeyCode, &KeyState)> =
+            self.keys.iter().filter(|(_, key)| filter(key)).collect();
+        keys.sort_by_key(|(_, key)| key.press_order);
+        keys.into_iter()
+            .map(|(code, key)| (*code, key.modifiers))
+            .collect()
+    }
+
+    /// Updates the state with a key event and returns what it triggers,
+    /// `None` when the event doesn't change anything.
+    pub fn key_event(&mut self, event: KeyEvent, now: Instant) -> Option<KeyTrigger> {
+        match event.kind {
+            KeyEventKind::Release => {
+                self.reports_releases = true;
+                self.keys.remove(&event.code).map(|_| KeyTrigger::Release)
+            }
+            KeyEventKind::Press | KeyEventKind::Repeat => {
+                // Without releases, only events coming at the keyboard repeat rate are repeats
+                let repeated = event.kind == KeyEventKind::Repeat
+                    || (!self.reports_releases
+                        && self.keys.get(&event.code).is_some_and(|key| {
+                            now.saturating_duration_since(key.last_event_time) < REPEAT_INTERVAL
+                        }));
+                let press_order = match self.keys.get(&event.code) {
+                    Some(key) => key.press_order,
+                    None => {
+                        self.presses += 1;
+                        self.presses
+                    }
+                };
+                self.keys.insert(
+                    event.code,
+                    KeyState {
+                        modifiers: event.modifiers,
+                        last_event_time: now,
+                        repeating: repeated,
+                        press_order,
+                    },
+                );
+                if repeated {
+                    Some(KeyTrigger::Repeat)
+                } else {
+                    Some(KeyTrigger::Press)
+                }
+            }
+        }
+    }
+
+    /// Releases the keys that timed out, when the terminal doesn't report releases.
+    pub fn expire(&mut self, now: Instant) -> Vec<(KeyCode, KeyModifiers)> {
+        if self.reports_releases {
+            return Vec::new();
+        }
+        let expired = self.ordered_keys(|key| {
+            let timeout = if key.repeating {
+                REPEAT_HOLD_TIMEOUT
+            } else {
+                PRESS_HOLD_TIMEOUT
+            };
+            now.saturating_duration_since(key.last_event_time) >= timeout
+        });
+        for (code, _) in expired.iter() {
+            self.keys.remove(code);
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_taps_are_presses() {
+        let mut input = InputState::new();
+        let now = Instant::now();
+        let key = KeyEvent::new(KeyCode::Right, KeyModifiers::NONE);
+
+        // Two taps of a terminal not reporting releases, the key is still down on the second one
+        assert_eq!(input.key_event(key, now), Some(KeyTrigger::Press));
+        let tapped = now + Duration::from_millis(300);
+        assert_eq!(input.key_event(key, tapped), Some(KeyTrigger::Press));
+        assert!(!input.is_held(KeyCode::Right));
+
+        // Events at the keyboard repeat rate are repeats
+        let repeated = tapped + Duration::from_millis(40);
+        assert_eq!(input.key_event(key, repeated), Some(KeyTrigger::Repeat));
+        assert!(input.is_held(KeyCode::Right));
+    }
+}
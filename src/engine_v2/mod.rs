@@ -1,7 +1,20 @@
+pub mod arena;
+pub mod capabilities;
 pub mod collision;
 pub mod coords;
+pub mod debug;
+pub mod dialog;
 pub mod engine;
 pub mod entity;
+pub mod event_log;
+pub mod graphics;
+pub mod hud;
+pub mod input;
+pub mod options;
 pub mod position;
+pub mod random;
+pub mod renderer;
+pub mod replay;
 pub mod scene;
 pub mod size;
+pub mod weather;
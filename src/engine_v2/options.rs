@@ -0,0 +1,283 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::engine_v2::weather::Weather;
+
+/// Environment variable setting the default animation speed multiplier.
+pub const SPEED_ENV_VAR: &str = "CORETILUS_SPEED";
+/// Environment variable setting the default maximum run duration, in seconds.
+pub const MAX_DURATION_ENV_VAR: &str = "CORETILUS_MAX_DURATION";
+/// Environment variable setting the default random seed.
+pub const SEED_ENV_VAR: &str = "CORETILUS_SEED";
+/// Environment variable setting the default weather, `rain`, `snow` or `stars`.
+pub const WEATHER_ENV_VAR: &str = "CORETILUS_WEATHER";
+/// Environment variable asking for less motion when set to anything but `0`.
+pub const REDUCED_MOTION_ENV_VAR: &str = "CORETILUS_REDUCED_MOTION";
+
+static GLOBAL_OPTIONS: OnceLock<EngineOptions> = OnceLock::new();
+
+/// Options shared by every command and applied by the engine.
+///
+/// They are read from the environment, then from the `--speed <factor>`,
+/// `--max-duration <secs>`, `--seed <number>`, `--print-seed`, `--record <file>`,
+/// `--replay <file>`, `--credits`, `--weather <kind>` and `--reduced-motion` arguments
+/// which are removed from the arguments seen by the command.
+/// They must come before the first positional argument or `--`,
+/// the following arguments are left to the command.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use coretilus::engine_v2::options::EngineOptions;
+/// use coretilus::engine_v2::weather::Weather;
+///
+/// let args = vec!["sl", "--speed", "2", "-a", "--max-duration=1.5"];
+/// let (options, args) =
+///     EngineOptions::parse(EngineOptions::default(), args.into_iter().map(String::from)).unwrap();
+/// assert_eq!(options.speed(), 2.0);
+/// assert_eq!(options.max_duration(), Some(Duration::from_millis(1500)));
+/// assert_eq!(args, vec!["sl", "-a"]);
+///
+/// let args = vec!["mr", "--seed=42", "--print-seed"];
+/// let (options, _) =
+///     EngineOptions::parse(EngineOptions::default(), args.into_iter().map(String::from)).unwrap();
+/// assert_eq!(options.seed(), Some(42));
+/// assert!(options.print_seed());
+///
+/// let args = vec!["gti", "--weather", "snow", "--reduced-motion"];
+/// let (options, _) =
+///     EngineOptions::parse(EngineOptions::default(), args.into_iter().map(String::from)).unwrap();
+/// assert_eq!(options.weather(), Some(Weather::Snow));
+/// assert!(options.reduced_motion());
+///
+/// // Words after the first positional argument or `--` are kept as they are
+/// let args = vec!["ehco", "say", "--speed"];
+/// let (options, args) =
+///     EngineOptions::parse(EngineOptions::default(), args.into_iter().map(String::from)).unwrap();
+/// assert_eq!(options.speed(), 1.0);
+/// assert_eq!(args, vec!["ehco", "say", "--speed"]);
+///
+/// let args = vec!["ehco", "--", "--credits", "please"];
+/// let (options, args) =
+///     EngineOptions::parse(EngineOptions::default(), args.into_iter().map(String::from)).unwrap();
+/// assert!(!options.credits());
+/// assert_eq!(args, vec!["ehco", "--credits", "please"]);
+///
+/// let bad_args = vec!["sl", "--speed", "0"];
+/// assert!(EngineOptions::parse(EngineOptions::default(), bad_args.into_iter().map(String::from)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineOptions {
+    // Animation speed multiplier, 2.0 runs twice as fast
+    speed: f64,
+    // Wall-clock duration after which the engine stops
+    max_duration: Option<Duration>,
+    // Seed of the random generator, drawn from entropy when not set
+    seed: Option<u64>,
+    // Print the seed when the command ends
+    print_seed: bool,
+    // List the artists of the scene instead of running it
+    credits: bool,
+    // Ambient particles drawn over every scene
+    weather: Option<Weather>,
+    // Keep the ambient effects still
+    reduced_motion: bool,
+    // File receiving the input events of the session
+    record: Option<PathBuf>,
+    // File whose input events are played back instead of the keyboard
+    replay: Option<PathBuf>,
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            max_duration: None,
+            seed: None,
+            print_seed: false,
+            credits: false,
+            weather: None,
+            reduced_motion: false,
+            record: None,
+            replay: None,
+        }
+    }
+}
+
+impl EngineOptions {
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.max_duration
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn print_seed(&self) -> bool {
+        self.print_seed
+    }
+
+    pub fn credits(&self) -> bool {
+        self.credits
+    }
+
+    pub fn weather(&self) -> Option<Weather> {
+        self.weather
+    }
+
+    /// Returns `true` when the user asked for fewer animations, effects are then kept still.
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    pub fn record(&self) -> Option<&PathBuf> {
+        self.record.as_ref()
+    }
+
+    pub fn replay(&self) -> Option<&PathBuf> {
+        self.replay.as_ref()
+    }
+
+    /// Replaces the speed, a replay must run at its recorded speed.
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed;
+    }
+
+    /// Reads the options from the environment variables.
+    pub fn from_env() -> Result<Self, String> {
+        let mut options = Self::default();
+        if let Ok(value) = std::env::var(SPEED_ENV_VAR) {
+            options.speed = parse_speed(&value)?;
+        }
+        if let Ok(value) = std::env::var(MAX_DURATION_ENV_VAR) {
+            options.max_duration = Some(parse_max_duration(&value)?);
+        }
+        if let Ok(value) = std::env::var(SEED_ENV_VAR) {
+            options.seed = Some(parse_seed(&value)?);
+        }
+        if let Ok(value) = std::env::var(WEATHER_ENV_VAR) {
+            options.weather = parse_weather(&value)?;
+        }
+        if let Ok(value) = std::env::var(REDUCED_MOTION_ENV_VAR) {
+            options.reduced_motion = !value.is_empty() && value != "0";
+        }
+        Ok(options)
+    }
+
+    /// Reads the options from the arguments on top of `defaults`,
+    /// returns them with the remaining arguments.
+    pub fn parse(
+        defaults: Self,
+        mut args: impl Iterator<Item = String>,
+    ) -> Result<(Self, Vec<String>), String> {
+        let mut options = defaults;
+        // The command name
+        let mut remaining_args: Vec<String> = args.next().into_iter().collect();
+        while let Some(argument) = args.next() {
+            if argument == "--" {
+                remaining_args.extend(args);
+                break;
+            }
+            if !argument.starts_with('-') {
+                remaining_args.push(argument);
+                remaining_args.extend(args);
+                break;
+            }
+            let (name, inline_value) = match argument.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (argument.clone(), None),
+            };
+            if name == "--print-seed" && inline_value.is_none() {
+                options.print_seed = true;
+                continue;
+            }
+            if name == "--credits" && inline_value.is_none() {
+                options.credits = true;
+                continue;
+            }
+            if name == "--reduced-motion" && inline_value.is_none() {
+                options.reduced_motion = true;
+                continue;
+            }
+            if ![
+                "--speed",
+                "--max-duration",
+                "--seed",
+                "--record",
+                "--replay",
+                "--weather",
+            ]
+            .contains(&name.as_str())
+            {
+                remaining_args.push(argument);
+                continue;
+            }
+            let value = inline_value
+                .or_else(|| args.next())
+                .ok_or(format!("missing value for {}", name))?;
+            match name.as_str() {
+                "--speed" => options.speed = parse_speed(&value)?,
+                "--max-duration" => options.max_duration = Some(parse_max_duration(&value)?),
+                "--seed" => options.seed = Some(parse_seed(&value)?),
+                "--weather" => options.weather = parse_weather(&value)?,
+                "--record" => options.record = Some(PathBuf::from(value)),
+                _ => options.replay = Some(PathBuf::from(value)),
+            }
+        }
+        if options.record.is_some() && options.replay.is_some() {
+            return Err(String::from("--record and --replay can't be used together"));
+        }
+        Ok((options, remaining_args))
+    }
+
+    /// Sets the options used by every engine of the process, only the first call is kept.
+    pub fn init_global(options: Self) {
+        let _ = GLOBAL_OPTIONS.set(options);
+    }
+
+    /// Returns the process options, or the defaults when they were never set.
+    pub fn global() -> Self {
+        GLOBAL_OPTIONS.get().cloned().unwrap_or_default()
+    }
+}
+
+fn parse_speed(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!(
+            "invalid speed '{}', expected a positive factor",
+            value
+        )),
+    }
+}
+
+fn parse_max_duration(value: &str) -> Result<Duration, String> {
+    match value.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs > 0.0 => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!(
+            "invalid max duration '{}', expected a positive number of seconds",
+            value
+        )),
+    }
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    value
+        .parse::<u64>()
+        .map_err(|_| format!("invalid seed '{}', expected a positive integer", value))
+}
+
+/// Parses a weather name, `none` turns the weather off.
+fn parse_weather(value: &str) -> Result<Option<Weather>, String> {
+    match value {
+        "none" => Ok(None),
+        _ => Weather::from_name(value).map(Some).ok_or(format!(
+            "invalid weather '{}', expected rain, snow, stars or none",
+            value
+        )),
+    }
+}
@@ -0,0 +1,92 @@
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::sync::OnceLock;
+
+use rand::SeedableRng;
+use rand::TryRng;
+use rand::rngs::StdRng;
+
+/// Sub-stream of the weather overlay, see [`sub_rng`].
+pub const WEATHER_STREAM: u64 = 0x5745_4154_4845_5200;
+
+static SEED: OnceLock<u64> = OnceLock::new();
+
+thread_local! {
+    // Seeded on first use from the process seed
+    static RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Sets the seed of the process random generator, only the first call is kept
+/// and it must happen before the generator is used.
+pub fn init_seed(seed: u64) {
+    let _ = SEED.set(seed);
+}
+
+/// Returns the seed of the process random generator, drawn from entropy when it was never set.
+pub fn seed() -> u64 {
+    *SEED.get_or_init(rand::random)
+}
+
+/// Returns the random generator shared by the engine and the commands.
+///
+/// All the draws come from a single sequence seeded with [`seed`],
+/// so a run can be reproduced by reusing its seed.
+/// The only other sequences are the sub-streams of [`sub_rng`].
+///
+/// ```rust
+/// use coretilus::engine_v2::random;
+/// use rand::RngExt;
+///
+/// let value: u32 = random::rng().random_range(0..10);
+/// assert!(value < 10);
+/// ```
+pub fn rng() -> EngineRng {
+    EngineRng
+}
+
+/// Returns a generator of its own, seeded from [`seed`] and `stream`.
+///
+/// It is kept for the draws that must not shift the shared sequence of [`rng`],
+/// like the ones of an optional overlay: a run gives the same objects with or without it.
+/// The streams are the `*_STREAM` constants of this module, currently [`WEATHER_STREAM`].
+///
+/// ```rust
+/// use coretilus::engine_v2::random;
+/// use rand::RngExt;
+///
+/// let mut weather = random::sub_rng(random::WEATHER_STREAM);
+/// let mut again = random::sub_rng(random::WEATHER_STREAM);
+/// assert_eq!(weather.random::<u64>(), again.random::<u64>());
+/// ```
+pub fn sub_rng(stream: u64) -> StdRng {
+    StdRng::seed_from_u64(seed().wrapping_add(stream))
+}
+
+/// Handle on the process random generator, see [`rng`].
+#[derive(Debug, Clone, Copy)]
+pub struct EngineRng;
+
+impl EngineRng {
+    fn with<T>(&mut self, f: impl FnOnce(&mut StdRng) -> T) -> T {
+        RNG.with(|rng| {
+            let mut rng = rng.borrow_mut();
+            f(rng.get_or_insert_with(|| StdRng::seed_from_u64(seed())))
+        })
+    }
+}
+
+impl TryRng for EngineRng {
+    type Error = Infallible;
+
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        self.with(|rng| rng.try_next_u32())
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        self.with(|rng| rng.try_next_u64())
+    }
+
+    fn try_fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), Self::Error> {
+        self.with(|rng| rng.try_fill_bytes(dst))
+    }
+}
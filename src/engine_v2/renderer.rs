@@ -0,0 +1,328 @@
+use std::io::Write;
+use std::io::stdout;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::engine_v2::graphics::GraphicsProtocol;
+use crate::engine_v2::graphics::GraphicsWriter;
+use crate::engine_v2::graphics::ImagePlacement;
+use crate::engine_v2::graphics::cell_pixel_size;
+use crate::engine_v2::size::Size;
+
+/// The frame waiting for the writer, a newer frame replaces it until it is printed.
+#[derive(Default)]
+struct Pending {
+    frame: Option<(Vec<Vec<char>>, Vec<ImagePlacement>)>,
+    // The writer is printing a frame
+    writing: bool,
+    // No more frames will be submitted
+    closed: bool,
+    // The writer thread ended, the terminal may be gone
+    stopped: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    pending: Mutex<Pending>,
+    changed: Condvar,
+}
+
+/// Prints the frames built by the engine from a dedicated thread.
+///
+/// A slow terminal only delays the output: the simulation keeps its cadence
+/// and a frame submitted while the writer is busy replaces the one waiting,
+/// so the writer always prints the most recent one once it is done.
+/// Bitmaps are drawn after the text of the frame with the graphics protocol, if any.
+pub struct Renderer {
+    shared: Arc<Shared>,
+    writer: Option<JoinHandle<()>>,
+    // Duration of the last terminal write, in nanoseconds
+    frame_time: Arc<AtomicU64>,
+    dropped_frames: usize,
+}
+
+impl Renderer {
+    /// Starts the writer thread.
+    pub fn spawn(graphics: Option<GraphicsProtocol>) -> Self {
+        Self::spawn_with_output(graphics, stdout())
+    }
+
+    fn spawn_with_output(
+        graphics: Option<GraphicsProtocol>,
+        output: impl Write + Send + 'static,
+    ) -> Self {
+        let shared = Arc::new(Shared::default());
+        let writer_shared = shared.clone();
+        let frame_time = Arc::new(AtomicU64::new(0));
+        let writer_frame_time = frame_time.clone();
+        let writer = std::thread::Builder::new()
+            .name(String::from("coretilus-renderer"))
+            .spawn(move || write_frames(&writer_shared, output, writer_frame_time, graphics))
+            .expect("Can't start the render thread");
+        Self {
+            shared,
+            writer: Some(writer),
+            frame_time,
+            dropped_frames: 0,
+        }
+    }
+
+    /// Hands a frame and its bitmaps to the writer, the frame it didn't print yet is dropped.
+    /// Returns `false` when the writer stopped.
+    pub fn submit(&mut self, screen: Vec<Vec<char>>, placements: Vec<ImagePlacement>) -> bool {
+        let mut pending = self.shared.pending.lock().unwrap();
+        if pending.stopped {
+            self.dropped_frames += 1;
+            return false;
+        }
+        if pending.frame.replace((screen, placements)).is_some() {
+            self.dropped_frames += 1;
+        }
+        self.shared.changed.notify_all();
+        true
+    }
+
+    /// Time spent writing the last printed frame to the terminal.
+    pub fn frame_time(&self) -> Duration {
+        Duration::from_nanos(self.frame_time.load(Ordering::Relaxed))
+    }
+
+    pub fn dropped_frames(&self) -> usize {
+        self.dropped_frames
+    }
+
+    /// Waits until the writer printed the pending frame and is idle,
+    /// so the terminal can be used by someone else.
+    pub fn sync(&self) {
+        let mut pending = self.shared.pending.lock().unwrap();
+        while (pending.frame.is_some() || pending.writing) && !pending.stopped {
+            pending = self.shared.changed.wait(pending).unwrap();
+        }
+    }
+
+    /// Prints the pending frame and stops the writer thread.
+    pub fn finish(&mut self) {
+        self.shared.pending.lock().unwrap().closed = true;
+        self.shared.changed.notify_all();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+impl Drop for Renderer {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+fn write_frames(
+    shared: &Shared,
+    mut output: impl Write,
+    frame_time: Arc<AtomicU64>,
+    graphics: Option<GraphicsProtocol>,
+) {
+    let mut graphics = graphics.map(|protocol| GraphicsWriter::new(protocol, cell_pixel_size()));
+    loop {
+        let (screen, placements) = {
+            let mut pending = shared.pending.lock().unwrap();
+            while pending.frame.is_none() && !pending.closed {
+                pending = shared.changed.wait(pending).unwrap();
+            }
+            // Closed once the last frame was printed
+            let Some(frame) = pending.frame.take() else {
+                break;
+            };
+            pending.writing = true;
+            frame
+        };
+
+        // Print on screen
+        let write_start_time = Instant::now();
+        let buffer = encode_frame(&screen, &placements, graphics.as_mut());
+        let written = output
+            .write_all(buffer.as_bytes())
+            .and_then(|_| output.flush());
+        frame_time.store(
+            write_start_time.elapsed().as_nanos() as u64,
+            Ordering::Relaxed,
+        );
+
+        shared.pending.lock().unwrap().writing = false;
+        shared.changed.notify_all();
+        if written.is_err() {
+            break;
+        }
+    }
+    if let Some(graphics) = graphics {
+        let _ = output.write_all(graphics.finish().as_bytes());
+        let _ = output.flush();
+    }
+    shared.pending.lock().unwrap().stopped = true;
+    shared.changed.notify_all();
+}
+
+/// Bytes printing a frame: its text from the top left corner, then its bitmaps.
+fn encode_frame(
+    screen: &[Vec<char>],
+    placements: &[ImagePlacement],
+    graphics: Option<&mut GraphicsWriter>,
+) -> String {
+    let mut buffer = String::new();
+    buffer.push_str("\x1B[H"); // Move cursor to the top
+    for line in screen.iter() {
+        buffer.extend(line.iter());
+    }
+    if let Some(graphics) = graphics {
+        buffer.push_str(&graphics.encode_placements(placements));
+    }
+    buffer
+}
+
+/// Clips or pads the screen to the size of the terminal it is printed on.
+/// Lines are printed back to back, so each must fill a whole terminal line.
+pub(crate) fn fit_screen(mut screen: Vec<Vec<char>>, size: Size) -> Vec<Vec<char>> {
+    screen.truncate(size.height() as usize);
+    for line in screen.iter_mut() {
+        line.resize(size.width() as usize, ' ');
+    }
+    screen
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::engine_v2::graphics::Bitmap;
+
+    fn red_placement(cells: Size) -> ImagePlacement {
+        ImagePlacement {
+            bitmap: Arc::new(Bitmap::from_pixel_art("r", &[('r', [255, 0, 0, 255])])),
+            column: 2,
+            row: 1,
+            cells,
+        }
+    }
+
+    #[test]
+    fn test_ascii_fallback() {
+        let screen = vec![vec!['(', ')'], vec![' ', '#']];
+        let placements = [red_placement(Size::new(2, 1))];
+        let buffer = encode_frame(&screen, &placements, None);
+
+        // Only the text, without any image escape sequence
+        assert_eq!(buffer, "\x1B[H() #");
+    }
+
+    #[test]
+    fn test_kitty_stream() {
+        let screen = vec![vec!['(', ')']];
+        let placements = [red_placement(Size::new(2, 1))];
+        let id = placements[0].bitmap.id();
+        let mut graphics = GraphicsWriter::new(GraphicsProtocol::Kitty, Size::new(10, 20));
+        assert_eq!(graphics.finish(), "");
+
+        let first = encode_frame(&screen, &placements, Some(&mut graphics));
+        let place = format!("\x1b_Ga=p,i={id},c=2,r=1,C=1,q=2\x1b\\");
+        assert!(first.starts_with("\x1B[H()\x1b_Ga=d,d=a,q=2\x1b\\\x1b[2;3H"));
+        assert!(first.contains(&format!("\x1b_Ga=t,f=32,s=1,v=1,i={id},")));
+        assert!(first.ends_with(&place));
+
+        // The bitmap is sent once, then only placed again
+        let second = encode_frame(&screen, &placements, Some(&mut graphics));
+        assert_eq!(
+            second,
+            format!("\x1B[H()\x1b_Ga=d,d=a,q=2\x1b\\\x1b[2;3H{place}")
+        );
+
+        // The bitmaps are freed on exit
+        assert_eq!(graphics.finish(), "\x1b_Ga=d,d=A,q=2\x1b\\");
+    }
+
+    #[test]
+    fn test_sixel_stream() {
+        let screen = vec![vec!['(', ')']];
+        let placements = [red_placement(Size::new(2, 1))];
+        let mut graphics = GraphicsWriter::new(GraphicsProtocol::Sixel, Size::new(3, 6));
+
+        // The 1x1 bitmap covers its 2x1 cells of 3x6 pixels
+        let buffer = encode_frame(&screen, &placements, Some(&mut graphics));
+        assert!(buffer.starts_with("\x1B[H()\x1b[2;3H\x1bP0;1;0q\"1;1;6;6#0;2;100;0;0"));
+        // One band of 6 red pixels by 6
+        assert!(buffer.contains("#0!6~"));
+        assert!(buffer.ends_with("\x1b\\"));
+
+        // Nothing is kept by the terminal
+        assert_eq!(graphics.finish(), "");
+    }
+
+    /// Blocks the writes until the gate is opened.
+    struct GatedOutput {
+        gate: Arc<(Mutex<bool>, Condvar)>,
+        written: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl Write for GatedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let (open, opened) = &*self.gate;
+            let mut open = open.lock().unwrap();
+            while !*open {
+                open = opened.wait(open).unwrap();
+            }
+            self.written.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_latest_frame_wins() {
+        let gate = Arc::new((Mutex::new(false), Condvar::new()));
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let output = GatedOutput {
+            gate: gate.clone(),
+            written: written.clone(),
+        };
+        let mut renderer = Renderer::spawn_with_output(None, output);
+
+        // The writer takes the first frame and blocks on the terminal
+        assert!(renderer.submit(vec![vec!['1']], Vec::new()));
+        while renderer.shared.pending.lock().unwrap().frame.is_some() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        for frame in ['2', '3', '4'] {
+            assert!(renderer.submit(vec![vec![frame]], Vec::new()));
+        }
+        // The frames replaced before being printed are dropped
+        assert_eq!(renderer.dropped_frames(), 2);
+
+        *gate.0.lock().unwrap() = true;
+        gate.1.notify_all();
+        renderer.finish();
+        let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+        assert_eq!(written, "\x1B[H1\x1B[H4");
+    }
+
+    #[test]
+    fn test_fit_screen() {
+        let screen = vec![
+            vec!['a', 'b', 'c'],
+            vec!['d', 'e', 'f'],
+            vec!['g', 'h', 'i'],
+        ];
+        let clipped = fit_screen(screen.clone(), Size::new(2, 2));
+        assert_eq!(clipped, vec![vec!['a', 'b'], vec!['d', 'e']]);
+        let padded = fit_screen(screen, Size::new(4, 3));
+        assert_eq!(padded[2], vec!['g', 'h', 'i', ' ']);
+    }
+}
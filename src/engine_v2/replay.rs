@@ -0,0 +1,459 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crossterm::event::Event;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
+
+use crate::engine_v2::size::Size;
+use crate::storage::escape;
+use crate::storage::unescape;
+
+/// Version of the replay file format written by this build.
+pub const REPLAY_VERSION: u32 = 1;
+/// First line of every replay file, followed by the format version.
+const HEADER: &str = "# coretilus replay v";
+
+static SESSION: Mutex<Session> = Mutex::new(Session::Off);
+
+enum Session {
+    Off,
+    Recording(BufWriter<File>),
+    Replaying(ReplayHeader, VecDeque<ReplayRun>),
+}
+
+/// What must be restored before replaying a recorded session.
+#[derive(Debug, Clone)]
+pub struct ReplayHeader {
+    pub command: String,
+    pub seed: u64,
+    pub terminal_size: Size,
+    pub speed: f64,
+    pub args: Vec<String>,
+}
+
+/// The input events of a single engine run, by simulation step.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayRun {
+    events: VecDeque<(usize, Event)>,
+    end_step: usize,
+    killed: bool,
+}
+
+impl ReplayRun {
+    /// Removes and returns the events read during `step`.
+    pub fn take_events(&mut self, step: usize) -> Vec<Event> {
+        let mut events = Vec::new();
+        while let Some((event_step, _)) = self.events.front() {
+            if *event_step > step {
+                break;
+            }
+            events.extend(self.events.pop_front().map(|(_, event)| event));
+        }
+        events
+    }
+
+    /// Last step simulated by the recorded run.
+    pub fn end_step(&self) -> usize {
+        self.end_step
+    }
+
+    /// Returns `true` when the recorded run was interrupted.
+    pub fn killed(&self) -> bool {
+        self.killed
+    }
+}
+
+impl Session {
+    /// Writes the header to `path`, the runs are recorded after it.
+    fn recording(path: &Path, header: &ReplayHeader) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}{}", HEADER, REPLAY_VERSION)?;
+        writeln!(writer, "command={}", escape(&header.command))?;
+        writeln!(writer, "seed={}", header.seed)?;
+        writeln!(
+            writer,
+            "terminal={}x{}",
+            header.terminal_size.width(),
+            header.terminal_size.height()
+        )?;
+        writeln!(writer, "speed={}", header.speed)?;
+        for arg in header.args.iter() {
+            writeln!(writer, "arg={}", escape(arg))?;
+        }
+        writer.flush()?;
+        Ok(Self::Recording(writer))
+    }
+
+    fn header(&self) -> Option<&ReplayHeader> {
+        match self {
+            Self::Replaying(header, _) => Some(header),
+            _ => None,
+        }
+    }
+
+    fn begin_run(&mut self) -> Option<ReplayRun> {
+        match self {
+            Self::Off => None,
+            Self::Recording(writer) => {
+                let _ = writeln!(writer, "run");
+                None
+            }
+            // Runs missing from the file replay without any input
+            Self::Replaying(_, runs) => Some(runs.pop_front().unwrap_or_default()),
+        }
+    }
+
+    fn record(&mut self, step: usize, event: &Event) {
+        if let Self::Recording(writer) = self
+            && let Some(encoded) = encode_event(event)
+        {
+            let _ = writeln!(writer, "{} {}", step, encoded);
+        }
+    }
+
+    fn end_run(&mut self, step: usize, killed: bool) {
+        if let Self::Recording(writer) = self {
+            let _ = writeln!(writer, "end {} {}", step, killed as u8);
+            let _ = writer.flush();
+        }
+    }
+}
+
+/// Starts recording the input events of the following engine runs to `path`.
+pub fn start_recording(path: &Path, header: &ReplayHeader) -> io::Result<()> {
+    *SESSION.lock().unwrap() = Session::recording(path, header)?;
+    Ok(())
+}
+
+/// Loads the replay file at `path`, the following engine runs play its events back.
+pub fn start_replay(path: &Path) -> io::Result<ReplayHeader> {
+    let (header, runs) = parse(&fs::read_to_string(path)?)?;
+    *SESSION.lock().unwrap() = Session::Replaying(header.clone(), runs.into());
+    Ok(header)
+}
+
+/// Returns `true` when the input events come from a replay file instead of the keyboard.
+pub fn is_replaying() -> bool {
+    matches!(*SESSION.lock().unwrap(), Session::Replaying(..))
+}
+
+/// Returns the header of the replay being played back.
+pub fn replayed_header() -> Option<ReplayHeader> {
+    SESSION.lock().unwrap().header().cloned()
+}
+
+/// Called when an engine starts running, returns the events to play back when replaying.
+pub(crate) fn begin_run() -> Option<ReplayRun> {
+    SESSION.lock().unwrap().begin_run()
+}
+
+/// Records an input event read during `step`, events that can't be encoded are skipped.
+pub(crate) fn record(step: usize, event: &Event) {
+    SESSION.lock().unwrap().record(step, event);
+}
+
+/// Called when an engine stops running.
+pub(crate) fn end_run(step: usize, killed: bool) {
+    SESSION.lock().unwrap().end_run(step, killed);
+}
+
+fn parse(content: &str) -> io::Result<(ReplayHeader, Vec<ReplayRun>)> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut lines = content.lines();
+    match lines.next().and_then(|line| line.strip_prefix(HEADER)) {
+        Some(version) if version.trim() == REPLAY_VERSION.to_string() => {}
+        Some(version) => return Err(invalid(format!("unsupported replay version {}", version))),
+        None => return Err(invalid(String::from("not a replay file"))),
+    }
+
+    let mut header = ReplayHeader {
+        command: String::new(),
+        seed: 0,
+        terminal_size: Size::new(0, 0),
+        speed: 1.0,
+        args: Vec::new(),
+    };
+    let mut runs: Vec<ReplayRun> = Vec::new();
+    for line in lines {
+        let invalid_line = || invalid(format!("invalid replay line {:?}", line));
+        if line == "run" {
+            runs.push(ReplayRun::default());
+            continue;
+        }
+        if let Some(run) = runs.last_mut() {
+            let mut fields = line.split(' ');
+            let first = fields.next().unwrap_or_default();
+            if first == "end" {
+                run.end_step = fields
+                    .next()
+                    .and_then(|step| step.parse().ok())
+                    .ok_or_else(invalid_line)?;
+                run.killed = fields.next() == Some("1");
+                continue;
+            }
+            let step = first.parse().map_err(|_| invalid_line())?;
+            let event = decode_event(&fields.collect::<Vec<_>>()).ok_or_else(invalid_line)?;
+            run.events.push_back((step, event));
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or_else(invalid_line)?;
+        match key {
+            "command" => header.command = unescape(value),
+            "seed" => header.seed = value.parse().map_err(|_| invalid_line())?,
+            "terminal" => {
+                let (width, height) = value.split_once('x').ok_or_else(invalid_line)?;
+                header.terminal_size = Size::new(
+                    width.parse().map_err(|_| invalid_line())?,
+                    height.parse().map_err(|_| invalid_line())?,
+                );
+            }
+            "speed" => header.speed = value.parse().map_err(|_| invalid_line())?,
+            "arg" => header.args.push(unescape(value)),
+            _ => return Err(invalid_line()),
+        }
+    }
+    Ok((header, runs))
+}
+
+/// Encodes a terminal event on a single line.
+///
+/// ```rust
+/// use crossterm::event::Event;
+/// use crossterm::event::KeyCode;
+/// use crossterm::event::KeyEvent;
+/// use crossterm::event::KeyEventKind;
+/// use crossterm::event::KeyModifiers;
+/// use coretilus::engine_v2::replay::decode_event;
+/// use coretilus::engine_v2::replay::encode_event;
+///
+/// let event = Event::Key(KeyEvent::new_with_kind(
+///     KeyCode::Char('x'),
+///     KeyModifiers::CONTROL,
+///     KeyEventKind::Repeat,
+/// ));
+/// let encoded = encode_event(&event).unwrap();
+/// assert_eq!(encoded, "key repeat 2 char:120");
+///
+/// let fields: Vec<&str> = encoded.split(' ').collect();
+/// assert_eq!(decode_event(&fields), Some(event));
+/// ```
+pub fn encode_event(event: &Event) -> Option<String> {
+    match event {
+        Event::Key(key) => {
+            let kind = match key.kind {
+                KeyEventKind::Press => "press",
+                KeyEventKind::Repeat => "repeat",
+                KeyEventKind::Release => "release",
+            };
+            Some(format!(
+                "key {} {} {}",
+                kind,
+                key.modifiers.bits(),
+                encode_key_code(key.code)?
+            ))
+        }
+        Event::Mouse(mouse) => {
+            let button = |button: MouseButton| match button {
+                MouseButton::Left => "left",
+                MouseButton::Right => "right",
+                MouseButton::Middle => "middle",
+            };
+            let kind = match mouse.kind {
+                MouseEventKind::Down(b) => format!("down-{}", button(b)),
+                MouseEventKind::Up(b) => format!("up-{}", button(b)),
+                MouseEventKind::Drag(b) => format!("drag-{}", button(b)),
+                MouseEventKind::Moved => String::from("moved"),
+                MouseEventKind::ScrollDown => String::from("scroll-down"),
+                MouseEventKind::ScrollUp => String::from("scroll-up"),
+                MouseEventKind::ScrollLeft => String::from("scroll-left"),
+                MouseEventKind::ScrollRight => String::from("scroll-right"),
+            };
+            Some(format!(
+                "mouse {} {} {} {}",
+                kind,
+                mouse.column,
+                mouse.row,
+                mouse.modifiers.bits()
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Decodes the fields of an event encoded by [`encode_event`].
+pub fn decode_event(fields: &[&str]) -> Option<Event> {
+    match fields {
+        ["key", kind, modifiers, code] => {
+            let kind = match *kind {
+                "press" => KeyEventKind::Press,
+                "repeat" => KeyEventKind::Repeat,
+                "release" => KeyEventKind::Release,
+                _ => return None,
+            };
+            let modifiers = KeyModifiers::from_bits_truncate(modifiers.parse().ok()?);
+            Some(Event::Key(KeyEvent::new_with_kind(
+                decode_key_code(code)?,
+                modifiers,
+                kind,
+            )))
+        }
+        ["mouse", kind, column, row, modifiers] => {
+            let button = |name: &str| match name {
+                "left" => Some(MouseButton::Left),
+                "right" => Some(MouseButton::Right),
+                "middle" => Some(MouseButton::Middle),
+                _ => None,
+            };
+            let kind = match kind.split_once('-') {
+                Some(("down", b)) => MouseEventKind::Down(button(b)?),
+                Some(("up", b)) => MouseEventKind::Up(button(b)?),
+                Some(("drag", b)) => MouseEventKind::Drag(button(b)?),
+                Some(("scroll", "down")) => MouseEventKind::ScrollDown,
+                Some(("scroll", "up")) => MouseEventKind::ScrollUp,
+                Some(("scroll", "left")) => MouseEventKind::ScrollLeft,
+                Some(("scroll", "right")) => MouseEventKind::ScrollRight,
+                None if *kind == "moved" => MouseEventKind::Moved,
+                _ => return None,
+            };
+            Some(Event::Mouse(MouseEvent {
+                kind,
+                column: column.parse().ok()?,
+                row: row.parse().ok()?,
+                modifiers: KeyModifiers::from_bits_truncate(modifiers.parse().ok()?),
+            }))
+        }
+        _ => None,
+    }
+}
+
+fn encode_key_code(code: KeyCode) -> Option<String> {
+    let name = match code {
+        KeyCode::Char(char) => return Some(format!("char:{}", char as u32)),
+        KeyCode::F(number) => return Some(format!("f:{}", number)),
+        KeyCode::Backspace => "backspace",
+        KeyCode::Enter => "enter",
+        KeyCode::Left => "left",
+        KeyCode::Right => "right",
+        KeyCode::Up => "up",
+        KeyCode::Down => "down",
+        KeyCode::Home => "home",
+        KeyCode::End => "end",
+        KeyCode::PageUp => "pageup",
+        KeyCode::PageDown => "pagedown",
+        KeyCode::Tab => "tab",
+        KeyCode::BackTab => "backtab",
+        KeyCode::Delete => "delete",
+        KeyCode::Insert => "insert",
+        KeyCode::Esc => "esc",
+        _ => return None,
+    };
+    Some(String::from(name))
+}
+
+fn decode_key_code(code: &str) -> Option<KeyCode> {
+    if let Some(char) = code.strip_prefix("char:") {
+        return char
+            .parse()
+            .ok()
+            .and_then(char::from_u32)
+            .map(KeyCode::Char);
+    }
+    if let Some(number) = code.strip_prefix("f:") {
+        return number.parse().ok().map(KeyCode::F);
+    }
+    let code = match code {
+        "backspace" => KeyCode::Backspace,
+        "enter" => KeyCode::Enter,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "esc" => KeyCode::Esc,
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, kind: KeyEventKind) -> Event {
+        Event::Key(KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind))
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("coretilus-{}.replay", std::process::id()));
+        let header = ReplayHeader {
+            command: String::from("mr"),
+            seed: 42,
+            terminal_size: Size::new(80, 24),
+            speed: 1.0,
+            args: vec![String::from("mr"), String::from("-r")],
+        };
+        let events = [
+            (3, key(KeyCode::Left, KeyEventKind::Press)),
+            (3, key(KeyCode::Char('d'), KeyEventKind::Press)),
+            (7, key(KeyCode::Left, KeyEventKind::Release)),
+            (
+                12,
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column: 4,
+                    row: 2,
+                    modifiers: KeyModifiers::NONE,
+                }),
+            ),
+        ];
+        // A local session, the global one is read by the tests building scenes
+        let mut session = Session::recording(&path, &header).unwrap();
+        assert!(session.begin_run().is_none());
+        for (step, event) in events.iter() {
+            session.record(*step, event);
+        }
+        session.end_run(15, false);
+        drop(session);
+
+        let (replayed, runs) = parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        let _ = fs::remove_file(&path);
+        let mut session = Session::Replaying(replayed.clone(), runs.into());
+        assert_eq!(replayed.seed, 42);
+        let size = replayed.terminal_size;
+        assert_eq!((size.width(), size.height()), (80, 24));
+        assert_eq!(replayed.args, header.args);
+
+        let mut run = session.begin_run().unwrap();
+        assert_eq!(run.end_step(), 15);
+        assert!(!run.killed());
+        // Each event comes back on the step it was read, in the same order
+        for step in 0..=run.end_step() {
+            let expected: Vec<Event> = events
+                .iter()
+                .filter(|(event_step, _)| *event_step == step)
+                .map(|(_, event)| event.clone())
+                .collect();
+            assert_eq!(run.take_events(step), expected, "step {}", step);
+        }
+    }
+}
@@ -1,30 +1,48 @@
 //use std::io::stdout;
 
-use crossterm::event::KeyCode;
-
 //use crate::engine_v2::collision::Collision;
-use crate::engine_v2::entity::object::ObjectRef;
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::capabilities::Capabilities;
+use crate::engine_v2::coords::Coords;
+use crate::engine_v2::entity::object::Object;
+use crate::engine_v2::graphics::GraphicsProtocol;
+use crate::engine_v2::graphics::ImagePlacement;
 use crate::engine_v2::size::Size;
+use crate::engine_v2::weather::WeatherOverlay;
 
 pub struct Scene {
     terminal_size: Size,
+    graphics: Option<GraphicsProtocol>,
 }
 
 impl Scene {
     pub fn new(terminal_size: Size) -> Self {
-        Self { terminal_size }
+        Self {
+            terminal_size,
+            graphics: Capabilities::global().graphics(),
+        }
+    }
+
+    pub fn graphics(&self) -> Option<GraphicsProtocol> {
+        self.graphics
     }
     pub fn update(
         &mut self,
         tick_id: usize,
-        objects: &mut [ObjectRef],
+        objects: &mut ObjectArena,
         //collisions: &mut [Collision],
     ) {
         // Hooks
         //self.hooks.process(&self.objects);
-        for object in objects.iter() {
-            object.borrow_mut().update(tick_id, self.terminal_size);
+        for handle in objects.handles() {
+            // Take the object out of the arena so relative movements can look up their parent
+            let Some(mut object) = objects.take(handle) else {
+                continue;
+            };
+            object.update(tick_id, self.terminal_size, objects);
+            objects.restore(handle, object);
         }
+        objects.update_z_order();
         // collisions
         //for col in collisions.iter_mut() {
         //    if col.is_colliding(self.terminal_size) {
@@ -35,59 +53,34 @@ impl Scene {
         //self.hooks.process(&self.objects);
     }
 
-    pub fn handle_input(&self, key: KeyCode, objects: &mut [ObjectRef]) {
-        for object in objects.iter() {
-            let maybe_action = {
-                let object_borrow = object.borrow();
-                object_borrow.input_actions.get(&key).map(|a| a as *const _)
-            };
-            if let Some(action_ptr) = maybe_action {
-                let action: &dyn Fn(ObjectRef) = unsafe { &*action_ptr };
-                action(object.clone()); // ✅ Clone le Rc et passe par valeur
-            }
-        }
+    /// Converts a terminal cell position to scene coordinates,
+    /// terminal rows go down while the scene y axis goes up.
+    pub fn scene_coords(&self, column: u16, row: u16) -> Coords {
+        Coords::new(
+            column as i32,
+            self.terminal_size.height() as i32 - 1 - row as i32,
+            0,
+        )
+    }
+
+    pub fn blank_screen(&self) -> Vec<Vec<char>> {
+        vec![vec![' '; self.terminal_size.width() as usize]; self.terminal_size.height() as usize]
     }
 
-    pub fn build_screen(&self, _tick_id: usize, objects: &mut [ObjectRef]) -> Vec<Vec<char>> {
+    /// Draws the frames of the objects, except the ones drawn as bitmaps when `graphics` is set.
+    pub fn build_screen(
+        &self,
+        _tick_id: usize,
+        objects: &ObjectArena,
+        graphics: bool,
+        mut weather: Option<&WeatherOverlay>,
+    ) -> Vec<Vec<char>> {
         //let mut stdout = stdout();
 
-        let mut screen: Vec<Vec<char>> = vec![
-            vec![' '; self.terminal_size.width() as usize];
-            self.terminal_size.height() as usize
-        ];
-
-        // Order objects by z-coordinate
-        objects.sort_by_key(|object| object.borrow().coords().z());
-        /*panic!(
-            "{} - {} - {}\n{}- {}\n{} - {} - {}\n{} - {} - {}\n{} - {} - {}\n{} - {} - {}\n{}- {}\n{}- {}\n{}- {}\n{}- {}\n",
-            objects.len(),
-            objects[0].borrow().tdid(),
-            objects[0].borrow().coords().z(),
-            objects[1].borrow().tdid(),
-            objects[1].borrow().coords().z(),
-            objects[2].borrow().tdid(),
-            objects[2].borrow().coords().x(),
-            objects[2].borrow().coords().z(),
-            objects[3].borrow().tdid(),
-            objects[3].borrow().coords().x(),
-            objects[3].borrow().coords().z(),
-            objects[4].borrow().tdid(),
-            objects[4].borrow().coords().x(),
-            objects[4].borrow().coords().z(),
-            objects[5].borrow().tdid(),
-            objects[5].borrow().coords().x(),
-            objects[5].borrow().coords().z(),
-            objects[6].borrow().tdid(),
-            objects[6].borrow().coords().z(),
-            objects[7].borrow().tdid(),
-            objects[7].borrow().coords().z(),
-            objects[8].borrow().tdid(),
-            objects[8].borrow().coords().z(),
-            objects[9].borrow().tdid(),
-            objects[9].borrow().coords().z(),
-        );*/
-
-        for objectref in objects.iter() {
+        let mut screen = self.blank_screen();
+
+        // Objects are kept ordered by z-coordinate by the arena
+        for handle in objects.z_ordered() {
             /*
             sprite.compute_path(self.terminal_size);
             if !sprite.is_visible() {
@@ -131,7 +124,12 @@ impl Scene {
                 }
             }*/
 
-            let object = objectref.borrow();
+            let object = &objects[handle];
+            // The weather is drawn between the objects, at its own z-coordinate
+            if let Some(overlay) = weather.take_if(|overlay| overlay.layer() < object.coords().z())
+            {
+                overlay.draw(&mut screen, objects);
+            }
             let object_x = object.coords().x();
             let object_y = object.coords().y();
             let frame = object.current_frame();
@@ -140,8 +138,13 @@ impl Scene {
             if !object.visible() {
                 continue;
             }
+            self.draw_trail(&mut screen, object);
+            // Drawn as a bitmap by the renderer
+            if graphics && self.bitmap_placement(object).is_some() {
+                continue;
+            }
             // Prepare printing
-            for (dy, line) in frame.get_lines().iter().rev().enumerate() {
+            for (dy, line) in frame.glyphs().iter().rev().enumerate() {
                 let screen_y = self.terminal_size.height() as i32 - 1 - (object_y + dy as i32);
                 // Above the screen
                 if screen_y >= self.terminal_size.height() as i32 {
@@ -151,8 +154,7 @@ impl Scene {
                 if screen_y < 0 {
                     continue;
                 }
-                let chars: Vec<char> = line.chars().collect();
-                for (dx, &char) in chars.iter().enumerate() {
+                for (dx, &char) in line.iter().enumerate() {
                     let screen_x = object_x + dx as i32;
                     // On the right of the screen
                     if screen_x >= self.terminal_size.width() as i32 {
@@ -163,13 +165,89 @@ impl Scene {
                         continue;
                     }
 
+                    let char = match object.effect() {
+                        Some(effect) => effect.apply(char, dx, dy),
+                        None => char,
+                    };
                     if char != ' ' {
                         screen[screen_y as usize][screen_x as usize] = char;
                     }
                 }
             }
         }
+        if let Some(overlay) = weather {
+            overlay.draw(&mut screen, objects);
+        }
 
         screen
     }
+
+    /// Draws the ghosts left by an object under it, from the oldest.
+    fn draw_trail(&self, screen: &mut [Vec<char>], object: &Object) {
+        let Some(trail) = object.trail() else {
+            return;
+        };
+        let (width, height) = (
+            self.terminal_size.width() as i32,
+            self.terminal_size.height() as i32,
+        );
+        let mut draw = |x: i32, y: i32, char: char| {
+            if char != ' ' && (0..width).contains(&x) && (0..height).contains(&y) {
+                screen[(height - 1 - y) as usize][x as usize] = char;
+            }
+        };
+        let glyphs = object.current_frame().glyphs();
+        for (age, coords) in object.trail_positions().into_iter().enumerate().rev() {
+            match trail.cell() {
+                Some((dx, dy)) => draw(coords.x() + dx, coords.y() + dy, trail.glyph(' ', age)),
+                None => {
+                    for (dy, line) in glyphs.iter().rev().enumerate() {
+                        for (dx, &char) in line.iter().enumerate() {
+                            if char != ' ' {
+                                draw(
+                                    coords.x() + dx as i32,
+                                    coords.y() + dy as i32,
+                                    trail.glyph(char, age),
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the bitmaps drawn instead of the frames, ordered by z-coordinate.
+    pub fn build_placements(&self, objects: &ObjectArena) -> Vec<ImagePlacement> {
+        objects
+            .z_ordered()
+            .filter_map(|handle| self.bitmap_placement(&objects[handle]))
+            .collect()
+    }
+
+    /// Places the bitmap of an object over its frame, objects partly out of the screen
+    /// keep their frame: a bitmap drawn on the last row could scroll the terminal.
+    fn bitmap_placement(&self, object: &Object) -> Option<ImagePlacement> {
+        self.graphics?;
+        let bitmap = object.bitmap()?;
+        // Effects are drawn on the frame
+        if !object.visible() || object.effect().is_some() {
+            return None;
+        }
+        let size = object.size();
+        let (x, y) = (object.coords().x(), object.coords().y());
+        let fits = x >= 0
+            && y >= 0
+            && x + size.width() as i32 <= self.terminal_size.width() as i32
+            && y + size.height() as i32 <= self.terminal_size.height() as i32;
+        if !fits || size.width() == 0 || size.height() == 0 {
+            return None;
+        }
+        Some(ImagePlacement {
+            bitmap: bitmap.clone(),
+            column: x as u16,
+            row: (self.terminal_size.height() as i32 - y - size.height() as i32) as u16,
+            cells: size,
+        })
+    }
 }
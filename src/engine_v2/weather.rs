@@ -0,0 +1,418 @@
+use std::collections::VecDeque;
+
+use rand::RngExt;
+use rand::rngs::StdRng;
+
+use crate::engine_v2::arena::ObjectArena;
+use crate::engine_v2::arena::ObjectHandle;
+use crate::engine_v2::random;
+use crate::engine_v2::size::Size;
+
+/// Ticks for a rain drop to fall by one cell.
+const RAIN_TICKS: usize = 4;
+/// Ticks for a snowflake to fall by one cell.
+const SNOW_TICKS: usize = 24;
+/// Ticks between two twinkling stars.
+const TWINKLE_TICKS: usize = 40;
+/// Glyphs a star can take.
+const STAR_GLYPHS: [char; 4] = ['.', '.', '+', '*'];
+
+/// Ambient particles drawn over the scene, see [`WeatherOverlay`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    Rain,
+    Snow,
+    Stars,
+}
+
+impl Weather {
+    /// Returns the weather called `name` on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rain" => Some(Weather::Rain),
+            "snow" => Some(Weather::Snow),
+            "stars" => Some(Weather::Stars),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weather::Rain => "rain",
+            Weather::Snow => "snow",
+            Weather::Stars => "stars",
+        }
+    }
+
+    /// Returns the z-coordinate the particles are drawn at by default:
+    /// rain and snow fall in front of the objects, stars shine behind them.
+    pub fn default_layer(&self) -> i32 {
+        match self {
+            Weather::Rain | Weather::Snow => i32::MAX,
+            Weather::Stars => i32::MIN,
+        }
+    }
+
+    /// Returns how many cells of the screen there are for one particle.
+    fn cells_per_particle(&self) -> u32 {
+        match self {
+            Weather::Rain => 40,
+            Weather::Snow => 60,
+            Weather::Stars => 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    x: i32,
+    y: i32,
+    glyph: char,
+}
+
+/// A snowflake resting on an object, its position is relative to the object.
+/// Flakes without an object rest on the bottom of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Settled {
+    object: Option<ObjectHandle>,
+    x: i32,
+    y: i32,
+}
+
+/// Rain, snow or stars drawn at a z-coordinate of the scene, whatever the command.
+///
+/// The particles spread over the whole screen, their number grows with its size.
+/// They react to the visible objects with an active collider: rain drops stop on them,
+/// snowflakes settle on them and move along until the object goes away from under them.
+/// With reduced motion the particles are kept still.
+///
+/// ```rust
+/// use coretilus::engine_v2::arena::ObjectArena;
+/// use coretilus::engine_v2::entity::frame::Frame;
+/// use coretilus::engine_v2::entity::object::Object;
+/// use coretilus::engine_v2::entity::sprite::Sprite;
+/// use coretilus::engine_v2::entity::sprite_animation::SpriteAnimation;
+/// use coretilus::engine_v2::size::Size;
+/// use coretilus::engine_v2::weather::Weather;
+/// use coretilus::engine_v2::weather::WeatherOverlay;
+///
+/// assert_eq!(Weather::from_name("snow"), Some(Weather::Snow));
+/// assert_eq!(Weather::from_name("hail"), None);
+///
+/// // A roof at the bottom of a screen one cell wide
+/// let sprite = Sprite::new(SpriteAnimation::new_static(Frame::new("=")), true);
+/// let mut objects = ObjectArena::new();
+/// let roof = objects.insert(Object::new(1, String::from("roof"), vec![sprite], None));
+///
+/// let mut snow = WeatherOverlay::new(Weather::Snow, Size::new(1, 3), false);
+/// for tick_id in 1..=1000 {
+///     snow.update(tick_id, &objects);
+/// }
+/// let mut screen = vec![vec![' ']; 3];
+/// snow.draw(&mut screen, &objects);
+/// // The snow settled on the roof, the roof itself is never hidden
+/// assert_eq!(screen[1][0], '_');
+/// assert_eq!(screen[2][0], ' ');
+///
+/// // Without the roof the snow melts away
+/// objects[roof].set_visible(false);
+/// for tick_id in 1001..=1100 {
+///     snow.update(tick_id, &objects);
+/// }
+/// let mut screen = vec![vec![' ']; 3];
+/// snow.draw(&mut screen, &objects);
+/// assert_ne!(screen[1][0], '_');
+/// ```
+#[derive(Debug)]
+pub struct WeatherOverlay {
+    weather: Weather,
+    layer: i32,
+    reduced_motion: bool,
+    terminal_size: Size,
+    rng: StdRng,
+    particles: Vec<Particle>,
+    // Oldest first, the oldest flakes melt when there are too many
+    settled: VecDeque<Settled>,
+}
+
+impl WeatherOverlay {
+    /// Spreads the particles of `weather` over a screen of `terminal_size` cells.
+    pub fn new(weather: Weather, terminal_size: Size, reduced_motion: bool) -> Self {
+        let mut overlay = Self {
+            weather,
+            layer: weather.default_layer(),
+            reduced_motion,
+            terminal_size,
+            // Its own sequence, the draws of the commands and the replays don't change
+            rng: random::sub_rng(random::WEATHER_STREAM),
+            particles: Vec::new(),
+            settled: VecDeque::new(),
+        };
+        let area = terminal_size.width() * terminal_size.height();
+        let count = (area / weather.cells_per_particle()).max(1);
+        for _ in 0..count {
+            let particle = overlay.random_particle();
+            overlay.particles.push(particle);
+        }
+        overlay
+    }
+
+    pub fn weather(&self) -> Weather {
+        self.weather
+    }
+
+    /// Returns the z-coordinate the particles are drawn at.
+    pub fn layer(&self) -> i32 {
+        self.layer
+    }
+
+    /// Draws the particles at another z-coordinate, between the objects.
+    pub fn set_layer(&mut self, z: i32) {
+        self.layer = z;
+    }
+
+    /// Moves the particles, objects are taken into account at their position of the tick.
+    pub fn update(&mut self, tick_id: usize, objects: &ObjectArena) {
+        if self.reduced_motion || self.terminal_size.width() == 0 {
+            return;
+        }
+        match self.weather {
+            Weather::Rain if tick_id.is_multiple_of(RAIN_TICKS) => self.fall_rain(objects),
+            Weather::Snow if tick_id.is_multiple_of(SNOW_TICKS) => self.fall_snow(objects),
+            Weather::Stars if tick_id.is_multiple_of(TWINKLE_TICKS) => {
+                let index = self.rng.random_range(0..self.particles.len());
+                self.particles[index].glyph = STAR_GLYPHS[self.rng.random_range(0..4)];
+            }
+            _ => {}
+        }
+    }
+
+    /// Draws the particles on the screen, cells covered by an object are left untouched.
+    pub fn draw(&self, screen: &mut [Vec<char>], objects: &ObjectArena) {
+        let occupancy = Occupancy::new(self.terminal_size, objects);
+        let height = self.terminal_size.height() as i32;
+        let mut draw = |x: i32, y: i32, glyph: char| {
+            if occupancy.at(x, y).is_some() || y < 0 || y >= height {
+                return;
+            }
+            if let Some(cell) = screen
+                .get_mut((height - 1 - y) as usize)
+                .and_then(|line| line.get_mut(x as usize))
+            {
+                *cell = glyph;
+            }
+        };
+        for particle in &self.particles {
+            draw(particle.x, particle.y, particle.glyph);
+        }
+        for flake in &self.settled {
+            if let Some((x, y)) = self.settled_position(flake, objects) {
+                draw(x, y, '_');
+            }
+        }
+    }
+
+    fn fall_rain(&mut self, objects: &ObjectArena) {
+        let occupancy = Occupancy::new(self.terminal_size, objects);
+        for index in 0..self.particles.len() {
+            let drop = &mut self.particles[index];
+            drop.y -= 1;
+            // Nothing falls through the objects, there is no rain under them
+            if drop.y < 0 || occupancy.at(drop.x, drop.y).is_some() {
+                self.particles[index] = self.top_particle();
+            }
+        }
+    }
+
+    fn fall_snow(&mut self, objects: &ObjectArena) {
+        let occupancy = Occupancy::new(self.terminal_size, objects);
+        // Flakes whose object went away from under them melt
+        let settled = std::mem::take(&mut self.settled);
+        self.settled = settled
+            .into_iter()
+            .filter(|flake| match flake.object {
+                None => true,
+                Some(handle) => self
+                    .settled_position(flake, objects)
+                    .is_some_and(|(x, y)| occupancy.at(x, y - 1) == Some(handle)),
+            })
+            .collect();
+
+        let width = self.terminal_size.width() as i32;
+        let max_settled = self.terminal_size.width() as usize * 2;
+        for index in 0..self.particles.len() {
+            let mut flake = self.particles[index];
+            if self.rng.random_range(0..3) == 0 {
+                flake.x = (flake.x + self.rng.random_range(-1..=1)).rem_euclid(width);
+            }
+            let resting_on = match flake.y {
+                0 => Some(None),
+                _ => occupancy.at(flake.x, flake.y - 1).map(Some),
+            };
+            let Some(object) = resting_on else {
+                flake.y -= 1;
+                self.particles[index] = flake;
+                continue;
+            };
+            if occupancy.at(flake.x, flake.y).is_none() {
+                let (x, y) = match object.and_then(|handle| objects.get(handle)) {
+                    Some(object) => (flake.x - object.coords().x(), flake.y - object.coords().y()),
+                    None => (flake.x, flake.y),
+                };
+                let settled = Settled { object, x, y };
+                if !self.settled.contains(&settled) {
+                    if self.settled.len() >= max_settled {
+                        self.settled.pop_front();
+                    }
+                    self.settled.push_back(settled);
+                }
+            }
+            self.particles[index] = self.top_particle();
+        }
+    }
+
+    /// Returns the position of a settled flake on the screen, `None` when its object is gone.
+    fn settled_position(&self, flake: &Settled, objects: &ObjectArena) -> Option<(i32, i32)> {
+        match flake.object {
+            None => Some((flake.x, flake.y)),
+            Some(handle) => {
+                let object = objects.get(handle).filter(|object| object.visible())?;
+                Some((object.coords().x() + flake.x, object.coords().y() + flake.y))
+            }
+        }
+    }
+
+    /// A particle anywhere on the screen, stars stay in the upper part.
+    fn random_particle(&mut self) -> Particle {
+        let width = self.terminal_size.width().max(1) as i32;
+        let height = self.terminal_size.height().max(1) as i32;
+        let lowest = match self.weather {
+            Weather::Stars => height / 3,
+            _ => 0,
+        };
+        Particle {
+            x: self.rng.random_range(0..width),
+            y: self.rng.random_range(lowest..height),
+            glyph: match self.weather {
+                Weather::Rain => '|',
+                Weather::Snow => '*',
+                Weather::Stars => STAR_GLYPHS[self.rng.random_range(0..4)],
+            },
+        }
+    }
+
+    /// A new rain drop or snowflake at the top of the screen.
+    fn top_particle(&mut self) -> Particle {
+        let mut particle = self.random_particle();
+        particle.y = self.terminal_size.height() as i32 - 1;
+        particle
+    }
+}
+
+/// The object drawn in each cell of the screen, among the visible ones with an active collider.
+struct Occupancy {
+    size: Size,
+    cells: Vec<Option<ObjectHandle>>,
+}
+
+impl Occupancy {
+    fn new(size: Size, objects: &ObjectArena) -> Self {
+        let (width, height) = (size.width() as i32, size.height() as i32);
+        let mut cells = vec![None; (width * height) as usize];
+        for handle in objects.z_ordered() {
+            let object = &objects[handle];
+            if !object.visible() || !object.collider().is_active() {
+                continue;
+            }
+            let (object_x, object_y) = (object.coords().x(), object.coords().y());
+            for (dy, line) in object.current_frame().glyphs().iter().rev().enumerate() {
+                let y = object_y + dy as i32;
+                for (dx, &char) in line.iter().enumerate() {
+                    let x = object_x + dx as i32;
+                    if char != ' ' && (0..width).contains(&x) && (0..height).contains(&y) {
+                        cells[(y * width + x) as usize] = Some(handle);
+                    }
+                }
+            }
+        }
+        Self { size, cells }
+    }
+
+    fn at(&self, x: i32, y: i32) -> Option<ObjectHandle> {
+        let (width, height) = (self.size.width() as i32, self.size.height() as i32);
+        if x < 0 || y < 0 || x >= width || y >= height {
+            return None;
+        }
+        self.cells[(y * width + x) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine_v2::collision::Collider;
+    use crate::engine_v2::coords::Coords;
+    use crate::engine_v2::entity::frame::Frame;
+    use crate::engine_v2::entity::object::Object;
+    use crate::engine_v2::entity::sprite::Sprite;
+    use crate::engine_v2::entity::sprite_animation::SpriteAnimation;
+
+    fn positions(overlay: &WeatherOverlay) -> Vec<(i32, i32, char)> {
+        overlay
+            .particles
+            .iter()
+            .map(|particle| (particle.x, particle.y, particle.glyph))
+            .collect()
+    }
+
+    #[test]
+    fn test_rain_stops_on_collider() {
+        // A roof at the bottom of a screen one cell wide
+        let sprite = Sprite::new(SpriteAnimation::new_static(Frame::new("=")), true);
+        let mut objects = ObjectArena::new();
+        objects.insert(Object::new(1, String::from("roof"), vec![sprite], None));
+
+        let mut rain = WeatherOverlay::new(Weather::Rain, Size::new(1, 5), false);
+        rain.particles = vec![Particle {
+            x: 0,
+            y: 3,
+            glyph: '|',
+        }];
+        rain.update(RAIN_TICKS, &objects);
+        rain.update(2 * RAIN_TICKS, &objects);
+        assert_eq!(positions(&rain), vec![(0, 1, '|')]);
+        // The drop doesn't go through the roof, a new one falls from the top
+        rain.update(3 * RAIN_TICKS, &objects);
+        assert_eq!(positions(&rain), vec![(0, 4, '|')]);
+
+        // Without a collider the drop reaches the bottom
+        let sprite = Sprite::new(SpriteAnimation::new_static(Frame::new("=")), true);
+        let collider = Collider::new(Coords::new(0, 0, 0), Size::new(1, 1), false);
+        let mut objects = ObjectArena::new();
+        objects.insert(Object::new(
+            1,
+            String::from("roof"),
+            vec![sprite],
+            Some(collider),
+        ));
+        rain.update(4 * RAIN_TICKS, &objects);
+        rain.update(5 * RAIN_TICKS, &objects);
+        rain.update(6 * RAIN_TICKS, &objects);
+        rain.update(7 * RAIN_TICKS, &objects);
+        assert_eq!(positions(&rain), vec![(0, 0, '|')]);
+    }
+
+    #[test]
+    fn test_reduced_motion_freezes_particles() {
+        let objects = ObjectArena::new();
+        for weather in [Weather::Rain, Weather::Snow, Weather::Stars] {
+            let mut overlay = WeatherOverlay::new(weather, Size::new(20, 10), true);
+            let before = positions(&overlay);
+            for tick_id in 1..=1000 {
+                overlay.update(tick_id, &objects);
+            }
+            assert_eq!(positions(&overlay), before);
+            assert!(overlay.settled.is_empty());
+        }
+    }
+}
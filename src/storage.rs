@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Version of the storage file format written by this build.
+pub const SCHEMA_VERSION: u32 = 1;
+/// First line of every storage file, followed by the schema version.
+const HEADER: &str = "# coretilus storage v";
+/// Environment variable overriding the directory holding the storage files.
+pub const DATA_DIR_ENV_VAR: &str = "CORETILUS_DATA_DIR";
+
+/// Returns the directory holding the storage files of every command.
+///
+/// It is `$CORETILUS_DATA_DIR` when set, else `$XDG_DATA_HOME/coretilus`,
+/// else `~/.local/share/coretilus` (`%APPDATA%\coretilus` on Windows).
+pub fn data_dir() -> Option<PathBuf> {
+    let non_empty = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+    if let Some(dir) = non_empty(DATA_DIR_ENV_VAR) {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = non_empty("XDG_DATA_HOME") {
+        return Some(PathBuf::from(dir).join("coretilus"));
+    }
+    #[cfg(windows)]
+    if let Some(dir) = non_empty("APPDATA") {
+        return Some(PathBuf::from(dir).join("coretilus"));
+    }
+    non_empty("HOME").map(|home| PathBuf::from(home).join(".local/share/coretilus"))
+}
+
+/// A small key/value store persisted in a per-command file.
+///
+/// Values are read once when the store is opened and written back by [`Storage::save`],
+/// which replaces the file atomically so an interrupted command never leaves a truncated file.
+/// Each file starts with its schema version; files written by a newer version are refused
+/// instead of being overwritten.
+///
+/// ```rust
+/// use coretilus::storage::Storage;
+///
+/// let path = std::env::temp_dir().join(format!("coretilus-doctest-{}.store", std::process::id()));
+/// let mut storage = Storage::open_at(&path).unwrap();
+/// assert_eq!(storage.get("best"), None);
+///
+/// storage.set("best", 12.5).unwrap();
+/// assert_eq!(storage.increment("launches").unwrap(), 1);
+/// assert_eq!(storage.increment("launches").unwrap(), 2);
+/// // The key would be read back as a comment
+/// assert!(storage.set("#best", 1).is_err());
+/// storage.save().unwrap();
+///
+/// let storage = Storage::open_at(&path).unwrap();
+/// assert_eq!(storage.get_parsed::<f64>("best"), Some(12.5));
+/// assert_eq!(storage.get_parsed::<u64>("launches"), Some(2));
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Storage {
+    path: PathBuf,
+    values: BTreeMap<String, String>,
+}
+
+impl Storage {
+    /// Opens the store of a command in the [`data_dir`].
+    pub fn open(command: &str) -> io::Result<Self> {
+        let dir = data_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory found"))?;
+        Self::open_at(dir.join(format!("{}.store", command)))
+    }
+
+    /// Opens the store saved at `path`, it is empty when the file doesn't exist yet.
+    pub fn open_at(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let values = match fs::read_to_string(&path) {
+            Ok(content) => parse(&content)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => return Err(error),
+        };
+        Ok(Self { path, values })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Returns the value of `key` parsed as `T`, `None` when missing or invalid.
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|value| value.parse().ok())
+    }
+
+    /// Sets the value of `key`.
+    /// Keys are refused when empty, when they contain `=` or line breaks,
+    /// or when they start with `#` since such lines are comments in the file.
+    pub fn set(&mut self, key: &str, value: impl ToString) -> io::Result<()> {
+        if key.is_empty() || key.starts_with('#') || key.contains(['=', '\n', '\r']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid storage key {:?}", key),
+            ));
+        }
+        self.values.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.values.remove(key)
+    }
+
+    /// Adds one to the counter stored in `key` and returns its new value.
+    pub fn increment(&mut self, key: &str) -> io::Result<u64> {
+        let value = self.get_parsed::<u64>(key).unwrap_or(0) + 1;
+        self.set(key, value)?;
+        Ok(value)
+    }
+
+    /// Writes the store to a temporary file then moves it over the previous one.
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut content = format!("{}{}\n", HEADER, SCHEMA_VERSION);
+        for (key, value) in self.values.iter() {
+            content.push_str(&format!("{}={}\n", key, escape(value)));
+        }
+
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(format!(".{}.tmp", std::process::id()));
+        let temp_path = PathBuf::from(temp_path);
+        let result = fs::File::create(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(content.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_path, &self.path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result
+    }
+}
+
+/// Reads the values of a storage file, refusing the ones written by a newer version.
+fn parse(content: &str) -> io::Result<BTreeMap<String, String>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut lines = content.lines();
+    let version = match lines.next().and_then(|line| line.strip_prefix(HEADER)) {
+        Some(version) => version
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| invalid(format!("invalid storage version {:?}", version)))?,
+        None => return Err(invalid(String::from("missing storage header"))),
+    };
+    if version > SCHEMA_VERSION {
+        return Err(invalid(format!(
+            "storage version {} is newer than {}",
+            version, SCHEMA_VERSION
+        )));
+    }
+
+    let mut values = BTreeMap::new();
+    for line in lines {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("invalid storage line {:?}", line)))?;
+        values.insert(key.to_string(), unescape(value));
+    }
+    Ok(values)
+}
+
+/// Escapes the line breaks of a value so it fits on a single line.
+pub(crate) fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Reverts [`escape`].
+pub(crate) fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("coretilus-storage-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_refuse_newer_version() {
+        let dir = temp_dir("newer");
+        let path = dir.join("mr.store");
+        let content = format!("{}{}\nbest=1\n", HEADER, SCHEMA_VERSION + 1);
+        fs::write(&path, &content).unwrap();
+
+        let error = Storage::open_at(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        // The file is left as it is
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_save_leaves_no_temp_file() {
+        let dir = temp_dir("failed");
+        // A directory can't be replaced by the saved file
+        let path = dir.join("mr.store");
+        fs::create_dir(&path).unwrap();
+        let mut storage = Storage::open_at(dir.join("other.store")).unwrap();
+        storage.path = path.clone();
+        storage.set("launches", 1).unwrap();
+
+        assert!(storage.save().is_err());
+        let entries: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("mr.store")]);
+        assert!(path.is_dir());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,9 +4,19 @@ use terminal_size::Height;
 use terminal_size::Width;
 use terminal_size::terminal_size;
 
+use crate::engine_v2::replay::replayed_header;
 use crate::engine_v2::size::Size;
 
 pub fn get_terminal_size() -> Size {
+    // A replay is drawn at its recorded size so objects land at the same place
+    if let Some(header) = replayed_header() {
+        return header.terminal_size;
+    }
+    get_real_terminal_size()
+}
+
+/// Size of the terminal the frames are printed on, even when replaying.
+pub fn get_real_terminal_size() -> Size {
     match terminal_size() {
         Some((Width(w), Height(h))) => Size::new(w as u32, h as u32),
         None => {